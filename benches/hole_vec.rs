@@ -0,0 +1,287 @@
+// Compares the gap buffer against the previous `VecDeque` based implementation,
+// which moved the hole by rotating the deque. The short-lived benches also
+// include `SmallHoleVec`, which keeps short buffers inline.
+//
+// Run with `cargo bench`.
+
+use std::collections::VecDeque;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use hole_vec::{HoleVec, SmallHoleVec};
+
+const LEN: usize = 1 << 16;
+const EDITS: usize = 1 << 12;
+const PROMPTS: usize = 1 << 14;
+const PROMPT_LEN: usize = 48;
+
+#[derive(Default)]
+struct RotateHoleVec<T> {
+    vec: VecDeque<T>,
+    hole_position: usize,
+}
+
+impl<T> RotateHoleVec<T> {
+    fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    fn push_before_hole(&mut self, value: T) {
+        self.vec.push_back(value);
+        self.hole_position += 1;
+    }
+
+    fn pop_after_hole(&mut self) -> Option<T> {
+        (self.len() > self.hole_position).then(|| self.vec.pop_front().unwrap())
+    }
+
+    fn set_hole_position(&mut self, position: usize) {
+        if position > self.hole_position {
+            self.vec.rotate_left(position - self.hole_position);
+        } else {
+            self.vec.rotate_right(self.hole_position - position);
+        }
+        self.hole_position = position;
+    }
+
+    fn as_slices_before_hole(&self) -> (&[T], &[T]) {
+        let (after_hole, before_hole) = self.vec.as_slices();
+        let len_after_hole = self.len() - self.hole_position;
+        if len_after_hole <= after_hole.len() {
+            (&after_hole[len_after_hole..], before_hole)
+        } else {
+            (&before_hole[before_hole.len() - self.hole_position..], &[])
+        }
+    }
+}
+
+trait Buffer: Default {
+    fn len(&self) -> usize;
+    fn push_before_hole(&mut self, value: u32);
+    fn pop_after_hole(&mut self) -> Option<u32>;
+    fn set_hole_position(&mut self, position: usize);
+    fn sum_before_hole(&self) -> u32;
+}
+
+impl Buffer for HoleVec<u32> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn push_before_hole(&mut self, value: u32) {
+        self.push_before_hole(value)
+    }
+
+    fn pop_after_hole(&mut self) -> Option<u32> {
+        self.pop_after_hole()
+    }
+
+    fn set_hole_position(&mut self, position: usize) {
+        self.set_hole_position(position)
+    }
+
+    fn sum_before_hole(&self) -> u32 {
+        self.as_slices_before_hole().iter().sum()
+    }
+}
+
+impl Buffer for SmallHoleVec<u32, 64> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn push_before_hole(&mut self, value: u32) {
+        self.push_before_hole(value)
+    }
+
+    fn pop_after_hole(&mut self) -> Option<u32> {
+        self.pop_after_hole()
+    }
+
+    fn set_hole_position(&mut self, position: usize) {
+        self.set_hole_position(position)
+    }
+
+    fn sum_before_hole(&self) -> u32 {
+        self.as_slices_before_hole().iter().sum()
+    }
+}
+
+impl Buffer for RotateHoleVec<u32> {
+    fn len(&self) -> usize {
+        self.len()
+    }
+
+    fn push_before_hole(&mut self, value: u32) {
+        self.push_before_hole(value)
+    }
+
+    fn pop_after_hole(&mut self) -> Option<u32> {
+        self.pop_after_hole()
+    }
+
+    fn set_hole_position(&mut self, position: usize) {
+        self.set_hole_position(position)
+    }
+
+    fn sum_before_hole(&self) -> u32 {
+        let (a0, a1) = self.as_slices_before_hole();
+        a0.iter().chain(a1).sum()
+    }
+}
+
+// A small xorshift generator, so that both implementations see the same edits
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0 as usize
+    }
+}
+
+fn filled<B: Buffer>() -> B {
+    let mut buffer = B::default();
+    for value in 0..LEN as u32 {
+        buffer.push_before_hole(value);
+    }
+    buffer
+}
+
+fn typing<B: Buffer>() {
+    let mut buffer = B::default();
+    for value in 0..LEN as u32 {
+        buffer.push_before_hole(black_box(value));
+    }
+    black_box(&buffer);
+}
+
+fn random_edits<B: Buffer>() {
+    let mut buffer = filled::<B>();
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..EDITS {
+        buffer.set_hole_position(rng.next() % (buffer.len() + 1));
+        buffer.push_before_hole(black_box(0));
+        black_box(buffer.pop_after_hole());
+    }
+    black_box(&buffer);
+}
+
+fn nearby_edits<B: Buffer>() {
+    let mut buffer = filled::<B>();
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    let mut position = LEN / 2;
+    for _ in 0..EDITS * 16 {
+        position = (position + rng.next() % 64)
+            .saturating_sub(32)
+            .min(buffer.len());
+        buffer.set_hole_position(position);
+        buffer.push_before_hole(black_box(0));
+    }
+    black_box(&buffer);
+}
+
+fn sum_before_hole<B: Buffer>() {
+    let mut buffer = filled::<B>();
+    buffer.set_hole_position(LEN / 3);
+    for _ in 0..1024 {
+        black_box(buffer.sum_before_hole());
+    }
+}
+
+// Many short-lived buffers, like the prompts and search boxes of an editor:
+// type a line, go back to fix it up and read it out.
+fn prompts<B: Buffer>() {
+    let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+    for _ in 0..PROMPTS {
+        let mut buffer = B::default();
+        for value in 0..PROMPT_LEN as u32 {
+            buffer.push_before_hole(black_box(value));
+        }
+        for _ in 0..4 {
+            buffer.set_hole_position(rng.next() % (buffer.len() + 1));
+            black_box(buffer.pop_after_hole());
+            buffer.push_before_hole(black_box(0));
+        }
+        buffer.set_hole_position(buffer.len());
+        black_box(buffer.sum_before_hole());
+    }
+}
+
+fn bench(name: &str, f: fn()) -> Duration {
+    f();
+    let iterations = 10;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    let elapsed = start.elapsed() / iterations;
+    println!("{:<32} {:>12.3?}", name, elapsed);
+    elapsed
+}
+
+// The last implementation of each bench is the baseline the others are
+// compared against.
+type Bench = (&'static str, &'static [(&'static str, fn())]);
+
+fn main() {
+    let benches: [Bench; 5] = [
+        (
+            "typing",
+            &[
+                ("gap", typing::<HoleVec<u32>>),
+                ("rotate", typing::<RotateHoleVec<u32>>),
+            ],
+        ),
+        (
+            "random_edits",
+            &[
+                ("gap", random_edits::<HoleVec<u32>>),
+                ("rotate", random_edits::<RotateHoleVec<u32>>),
+            ],
+        ),
+        (
+            "nearby_edits",
+            &[
+                ("gap", nearby_edits::<HoleVec<u32>>),
+                ("rotate", nearby_edits::<RotateHoleVec<u32>>),
+            ],
+        ),
+        (
+            "sum_before_hole",
+            &[
+                ("gap", sum_before_hole::<HoleVec<u32>>),
+                ("rotate", sum_before_hole::<RotateHoleVec<u32>>),
+            ],
+        ),
+        (
+            "prompts",
+            &[
+                ("small", prompts::<SmallHoleVec<u32, 64>>),
+                ("gap", prompts::<HoleVec<u32>>),
+                ("rotate", prompts::<RotateHoleVec<u32>>),
+            ],
+        ),
+    ];
+
+    for (name, implementations) in benches.iter() {
+        let elapsed = implementations
+            .iter()
+            .map(|(implementation, f)| bench(&format!("{}/{}", name, implementation), *f))
+            .collect::<Vec<_>>();
+        let baseline = elapsed.last().unwrap();
+        for ((implementation, _), elapsed) in implementations.iter().zip(&elapsed).rev().skip(1) {
+            println!(
+                "{:<32} {:>11.2}x",
+                format!(
+                    "  {} vs {}",
+                    implementation,
+                    implementations.last().unwrap().0
+                ),
+                baseline.as_secs_f64() / elapsed.as_secs_f64()
+            );
+        }
+    }
+}
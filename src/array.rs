@@ -0,0 +1,946 @@
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{self, Chain, FusedIterator};
+use core::mem::{ManuallyDrop, MaybeUninit};
+use core::ops::{Index, IndexMut, Range, RangeBounds};
+use core::option;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+use crate::error::{check_bounds, range_bounds};
+use crate::iter::{Drain, Iter, IterMut};
+use crate::search::{self, FindIter};
+use crate::slice::{HoleSlice, HoleSliceMut};
+use crate::HoleVecError;
+
+// The values that did not fit into a `HoleArray`: those that were already
+// taken from the iterator, followed by the rest of the iterator
+pub type Overflow<'a, I> =
+    Chain<Chain<Drain<'a, <I as Iterator>::Item>, option::IntoIter<<I as Iterator>::Item>>, I>;
+
+// Hands back the values of `taken` and `value`, followed by the rest of `iter`
+fn overflow<I: Iterator>(
+    taken: Drain<'_, I::Item>,
+    value: Option<I::Item>,
+    iter: I,
+) -> Overflow<'_, I> {
+    taken.chain(value).chain(iter)
+}
+
+// A gap buffer with room for exactly `N` values, stored inline. It is laid out
+// like a `HoleVec`: the values before the hole at the start of `buf` and the
+// values after the hole at its end. Pushing into a full `HoleArray` hands the
+// value back instead of allocating.
+pub struct HoleArray<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    // Amount of values before the hole
+    hole_position: usize,
+    // Amount of values after the hole
+    after_hole: usize,
+}
+
+impl<T, const N: usize> Default for HoleArray<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> HoleArray<T, N> {
+    pub fn new() -> Self {
+        Self {
+            // An array of `MaybeUninit` does not need to be initialized
+            buf: unsafe { MaybeUninit::<[MaybeUninit<T>; N]>::uninit().assume_init() },
+            hole_position: 0,
+            after_hole: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.hole_position + self.after_hole
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == N
+    }
+
+    pub fn len_before_hole(&self) -> usize {
+        self.hole_position
+    }
+
+    pub fn len_after_hole(&self) -> usize {
+        self.after_hole
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    pub fn gap_len(&self) -> usize {
+        N - self.len()
+    }
+
+    fn ptr(&self) -> *const T {
+        self.buf.as_ptr() as *const T
+    }
+
+    fn mut_ptr(&mut self) -> *mut T {
+        self.buf.as_mut_ptr() as *mut T
+    }
+
+    fn after_hole_ptr(&mut self) -> *mut T {
+        let start = N - self.after_hole;
+        unsafe { self.mut_ptr().add(start) }
+    }
+
+    // Copies `amount` values between the slots at `from` and `to`
+    unsafe fn copy(&mut self, from: usize, to: usize, amount: usize) {
+        let ptr = self.mut_ptr();
+        ptr::copy(ptr.add(from), ptr.add(to), amount);
+    }
+
+    pub fn push_before_hole(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        unsafe { self.mut_ptr().add(self.hole_position).write(value) };
+        self.hole_position += 1;
+        Ok(())
+    }
+
+    pub fn push_after_hole(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.after_hole += 1;
+        unsafe { self.after_hole_ptr().write(value) };
+        Ok(())
+    }
+
+    pub fn pop_before_hole(&mut self) -> Option<T> {
+        self.hole_position = self.hole_position.checked_sub(1)?;
+        Some(unsafe { self.mut_ptr().add(self.hole_position).read() })
+    }
+
+    pub fn pop_after_hole(&mut self) -> Option<T> {
+        if self.after_hole == 0 {
+            return None;
+        }
+        let value = unsafe { self.after_hole_ptr().read() };
+        self.after_hole -= 1;
+        Some(value)
+    }
+
+    pub fn drain_before_hole(&mut self, amount: usize) -> Drain<'_, T> {
+        assert!(amount <= self.len_before_hole());
+        self.hole_position -= amount;
+        unsafe { Drain::new(self.mut_ptr().add(self.hole_position), amount) }
+    }
+
+    pub fn drain_after_hole(&mut self, amount: usize) -> Drain<'_, T> {
+        assert!(amount <= self.len_after_hole());
+        let start = self.after_hole_ptr();
+        self.after_hole -= amount;
+        unsafe { Drain::new(start, amount) }
+    }
+
+    // A `Drain` of no values, for an `Overflow` that hands back nothing but
+    // the iterator
+    fn no_values(&mut self) -> Drain<'_, T> {
+        unsafe { Drain::new(self.mut_ptr(), 0) }
+    }
+
+    // Pushes the values of `iter` in front of the hole until the `HoleArray` is
+    // full, and returns the first value that did not fit
+    fn push_all<I: Iterator<Item = T>>(&mut self, iter: &mut I) -> Option<T> {
+        iter.find_map(|value| self.push_before_hole(value).err())
+    }
+
+    // Pushes the values of `iter` in front of the hole until the `HoleArray` is
+    // full. Nothing is taken from `iter` if its `size_hint` already shows that
+    // the values do not fit. Otherwise the values that fit stay pushed, and the
+    // rest is handed back, starting with the one that did not fit.
+    pub fn extend_before_hole<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), Overflow<'_, I::IntoIter>> {
+        let mut iter = iter.into_iter();
+        if iter.size_hint().0 > self.gap_len() {
+            return Err(overflow(self.no_values(), None, iter));
+        }
+        match self.push_all(&mut iter) {
+            Some(value) => Err(overflow(self.no_values(), Some(value), iter)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn extend_after_hole<I: IntoIterator<Item = T>>(
+        &mut self,
+        iter: I,
+    ) -> Result<(), Overflow<'_, I::IntoIter>> {
+        let mut iter = iter.into_iter();
+        if iter.size_hint().0 > self.gap_len() {
+            return Err(overflow(self.no_values(), None, iter));
+        }
+        let hole_position = self.hole_position;
+        let rejected = self.push_all(&mut iter);
+        self.move_hole_left(self.hole_position - hole_position);
+        match rejected {
+            Some(value) => Err(overflow(self.no_values(), Some(value), iter)),
+            None => Ok(()),
+        }
+    }
+
+    // Nothing is cloned if the values do not fit, and all of them are handed
+    // back, as when `extend_before_hole` finds that out from the `size_hint`
+    pub fn extend_from_slice_before_hole<'a>(&mut self, values: &'a [T]) -> Result<(), &'a [T]>
+    where
+        T: Clone,
+    {
+        if values.len() > self.gap_len() {
+            return Err(values);
+        }
+        for value in values {
+            let _ = self.push_before_hole(value.clone());
+        }
+        Ok(())
+    }
+
+    pub fn extend_from_slice_after_hole<'a>(&mut self, values: &'a [T]) -> Result<(), &'a [T]>
+    where
+        T: Clone,
+    {
+        if values.len() > self.gap_len() {
+            return Err(values);
+        }
+        for value in values.iter().rev() {
+            let _ = self.push_after_hole(value.clone());
+        }
+        Ok(())
+    }
+
+    // Inserts `value` at `index`, unless the `HoleArray` is full
+    pub fn insert(&mut self, index: usize, value: T) -> Result<(), T> {
+        assert!(index <= self.len());
+        if self.is_full() {
+            return Err(value);
+        }
+        self.set_hole_position(index);
+        self.push_before_hole(value)
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "removal index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+        self.set_hole_position(index);
+        self.pop_after_hole().unwrap()
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        let (start, end) = range_bounds(range, self.len());
+        self.set_hole_position(start);
+        self.drain_after_hole(end - start)
+    }
+
+    // The replacement may take up the gap as well as the slots of the removed
+    // values. As many values as fit are taken into the gap right away, and the
+    // rest is inserted into the freed slots once the returned `ArraySplice` is
+    // dropped. That is only done if the `size_hint` of `replace_with` shows
+    // that the rest fits there, any values beyond its upper bound are dropped.
+    // Otherwise nothing changes, and all values taken from `replace_with` are
+    // handed back along with the rest of it.
+    pub fn splice<R, I>(
+        &mut self,
+        range: R,
+        replace_with: I,
+    ) -> Result<ArraySplice<'_, T, I::IntoIter, N>, Overflow<'_, I::IntoIter>>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = range_bounds(range, self.len());
+        let mut replace_with = replace_with.into_iter();
+        let (gap_len, removed) = (self.gap_len(), end - start);
+        if replace_with.size_hint().0 > gap_len + removed {
+            return Err(overflow(self.no_values(), None, replace_with));
+        }
+        // The taken values stay in the gap, outside of the values, until it is
+        // clear that all of them fit
+        let gap = unsafe { self.mut_ptr().add(self.hole_position) };
+        let mut taken = 0;
+        let mut pending = None;
+        for value in replace_with.by_ref() {
+            if taken == gap_len {
+                pending = Some(value);
+                break;
+            }
+            unsafe { gap.add(taken).write(value) };
+            taken += 1;
+        }
+        if pending.is_some()
+            && replace_with
+                .size_hint()
+                .1
+                .map_or(true, |upper| upper >= removed)
+        {
+            let taken = unsafe { Drain::new(gap, taken) };
+            return Err(overflow(taken, pending, replace_with));
+        }
+        self.insert_from_gap(taken, start);
+        // The `Drain` borrows from `array`, so that `array` stays usable once
+        // the `Drain` is dropped
+        let mut array = NonNull::from(self);
+        let drain = unsafe { array.as_mut() }.drain_after_hole(removed);
+        Ok(ArraySplice {
+            drain: ManuallyDrop::new(drain),
+            array,
+            pending,
+            replace_with,
+        })
+    }
+
+    // Inserts the `amount` values written to the start of the gap at `index`,
+    // by rotating them past the values between the hole and `index`
+    fn insert_from_gap(&mut self, amount: usize, index: usize) {
+        let hole_position = self.hole_position;
+        self.hole_position += amount;
+        if index <= hole_position {
+            self.as_mut_slices_before_hole()[index..].rotate_right(amount);
+            self.move_hole_left(hole_position - index);
+        } else {
+            self.move_hole_right(index - hole_position);
+            self.as_mut_slices_before_hole()[hole_position..].rotate_left(amount);
+        }
+    }
+
+    pub fn move_hole_right(&mut self, amount: usize) {
+        assert!(amount <= self.len_after_hole());
+        unsafe { self.copy(N - self.after_hole, self.hole_position, amount) };
+        self.hole_position += amount;
+        self.after_hole -= amount;
+    }
+
+    pub fn move_hole_left(&mut self, amount: usize) {
+        assert!(amount <= self.len_before_hole());
+        self.hole_position -= amount;
+        self.after_hole += amount;
+        unsafe { self.copy(self.hole_position, N - self.after_hole, amount) };
+    }
+
+    pub fn set_hole_position(&mut self, position: usize) {
+        assert!(position <= self.len());
+        if position > self.hole_position {
+            self.move_hole_right(position - self.hole_position);
+        } else {
+            self.move_hole_left(self.hole_position - position);
+        }
+    }
+
+    pub fn try_move_hole_right(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        check_bounds(amount, self.len_after_hole())?;
+        self.move_hole_right(amount);
+        Ok(())
+    }
+
+    pub fn try_move_hole_left(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        check_bounds(amount, self.len_before_hole())?;
+        self.move_hole_left(amount);
+        Ok(())
+    }
+
+    pub fn try_set_hole_position(&mut self, position: usize) -> Result<(), HoleVecError> {
+        check_bounds(position, self.len())?;
+        self.set_hole_position(position);
+        Ok(())
+    }
+
+    fn physical_index(&self, index: usize) -> usize {
+        if index < self.hole_position {
+            index
+        } else {
+            index + self.gap_len()
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len()).then(|| unsafe { &*self.ptr().add(self.physical_index(index)) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        (index < self.len()).then(|| unsafe {
+            let physical_index = self.physical_index(index);
+            &mut *self.mut_ptr().add(physical_index)
+        })
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len().checked_sub(1)?)
+    }
+
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (self.as_slices_before_hole(), self.as_slices_after_hole())
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        let (hole_position, after_hole) = (self.hole_position, self.after_hole);
+        let ptr = self.mut_ptr();
+        unsafe {
+            (
+                slice::from_raw_parts_mut(ptr, hole_position),
+                slice::from_raw_parts_mut(ptr.add(N - after_hole), after_hole),
+            )
+        }
+    }
+
+    pub fn as_slices_before_hole(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr(), self.hole_position) }
+    }
+
+    pub fn as_slices_after_hole(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr().add(N - self.after_hole), self.after_hole) }
+    }
+
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> HoleSlice<'_, T> {
+        HoleSlice::new(self.as_slices(), range)
+    }
+
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> HoleSliceMut<'_, T> {
+        HoleSliceMut::new(self.as_mut_slices(), range)
+    }
+
+    pub fn as_mut_slices_before_hole(&mut self) -> &mut [T] {
+        self.as_mut_slices().0
+    }
+
+    pub fn as_mut_slices_after_hole(&mut self) -> &mut [T] {
+        self.as_mut_slices().1
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slices())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.as_mut_slices())
+    }
+
+    pub fn iter_before_hole(&self) -> Iter<'_, T> {
+        Iter::new((self.as_slices_before_hole(), &[]))
+    }
+
+    pub fn iter_after_hole(&self) -> Iter<'_, T> {
+        Iter::new((&[], self.as_slices_after_hole()))
+    }
+
+    pub fn position<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<usize> {
+        self.iter().position(predicate)
+    }
+}
+
+impl<T: PartialEq, const N: usize> HoleArray<T, N> {
+    pub fn contains(&self, value: &T) -> bool {
+        let (a0, a1) = self.as_slices();
+        a0.contains(value) || a1.contains(value)
+    }
+
+    // Takes O(n * m) time, see `HoleVec::find`
+    pub fn find(&self, needle: &[T]) -> Option<usize> {
+        search::find_from(self.as_slices(), needle, 0, search::find_in_slice)
+    }
+
+    pub fn rfind(&self, needle: &[T]) -> Option<usize> {
+        search::rfind_to(self.as_slices(), needle, self.len(), search::rfind_in_slice)
+    }
+
+    pub fn find_iter<'a, 'b>(&'a self, needle: &'b [T]) -> FindIter<'a, 'b, T> {
+        FindIter::new(self.as_slices(), needle, search::find_in_slice)
+    }
+}
+
+#[cfg(feature = "memchr")]
+impl<const N: usize> HoleArray<u8, N> {
+    pub fn find_bytes(&self, needle: &[u8]) -> Option<usize> {
+        search::find_from(self.as_slices(), needle, 0, search::find_bytes)
+    }
+
+    pub fn rfind_bytes(&self, needle: &[u8]) -> Option<usize> {
+        search::rfind_to(self.as_slices(), needle, self.len(), search::rfind_bytes)
+    }
+
+    pub fn find_bytes_iter<'a, 'b>(&'a self, needle: &'b [u8]) -> FindIter<'a, 'b, u8> {
+        FindIter::new(self.as_slices(), needle, search::find_bytes)
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for HoleArray<T, N> {
+    fn clone(&self) -> Self {
+        let mut hole_array = Self::new();
+        for value in self.as_slices_before_hole() {
+            let _ = hole_array.push_before_hole(value.clone());
+        }
+        for value in self.as_slices_after_hole().iter().rev() {
+            let _ = hole_array.push_after_hole(value.clone());
+        }
+        hole_array
+    }
+}
+
+impl<T, const N: usize> Drop for HoleArray<T, N> {
+    fn drop(&mut self) {
+        let (a0, a1) = self.as_mut_slices();
+        unsafe {
+            ptr::drop_in_place(a0);
+            ptr::drop_in_place(a1);
+        }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for HoleArray<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        let len = self.len();
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for HoleArray<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len();
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for HoleArray<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<HoleArray<U, M>>
+    for HoleArray<T, N>
+{
+    fn eq(&self, other: &HoleArray<U, M>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for HoleArray<T, N> {}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for HoleArray<T, N> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<&[U]> for HoleArray<T, N> {
+    fn eq(&self, other: &&[U]) -> bool {
+        *self == **other
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]> for HoleArray<T, N> {
+    fn eq(&self, other: &[U; M]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for HoleArray<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for HoleArray<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for HoleArray<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+// Owns the values of a `HoleArray` and reads them out one by one, from both of
+// the ranges they were stored in.
+pub struct ArrayIntoIter<T, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    before_hole: Range<usize>,
+    after_hole: Range<usize>,
+}
+
+impl<T, const N: usize> ArrayIntoIter<T, N> {
+    fn new(hole_array: HoleArray<T, N>) -> Self {
+        let hole_array = ManuallyDrop::new(hole_array);
+        Self {
+            buf: unsafe { ptr::read(&hole_array.buf) },
+            before_hole: 0..hole_array.hole_position,
+            after_hole: N - hole_array.after_hole..N,
+        }
+    }
+
+    pub(crate) fn as_slices(&self) -> (&[T], &[T]) {
+        let slice = |range: &Range<usize>| unsafe {
+            slice::from_raw_parts(self.buf[range.clone()].as_ptr() as *const T, range.len())
+        };
+        (slice(&self.before_hole), slice(&self.after_hole))
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for ArrayIntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArrayIntoIter")
+            .field(&Iter::new(self.as_slices()))
+            .finish()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for ArrayIntoIter<T, N> {
+    fn clone(&self) -> Self {
+        let (a0, a1) = self.as_slices();
+        let mut hole_array = HoleArray::new();
+        for value in a0.iter().chain(a1) {
+            let _ = hole_array.push_before_hole(value.clone());
+        }
+        Self::new(hole_array)
+    }
+}
+
+impl<T, const N: usize> Iterator for ArrayIntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let index = self.before_hole.next().or_else(|| self.after_hole.next())?;
+        Some(unsafe { self.buf[index].as_ptr().read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for ArrayIntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        let index = self
+            .after_hole
+            .next_back()
+            .or_else(|| self.before_hole.next_back())?;
+        Some(unsafe { self.buf[index].as_ptr().read() })
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for ArrayIntoIter<T, N> {
+    fn len(&self) -> usize {
+        self.before_hole.len() + self.after_hole.len()
+    }
+}
+
+impl<T, const N: usize> FusedIterator for ArrayIntoIter<T, N> {}
+
+impl<T, const N: usize> Drop for ArrayIntoIter<T, N> {
+    fn drop(&mut self) {
+        for range in [self.before_hole.clone(), self.after_hole.clone()] {
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    self.buf[range.clone()].as_mut_ptr() as *mut T,
+                    range.len(),
+                ));
+            }
+        }
+    }
+}
+
+// The values removed by `HoleArray::splice`. The replacement values that did
+// not fit into the gap are inserted in their place once it is dropped.
+pub struct ArraySplice<'a, T, I: Iterator<Item = T>, const N: usize> {
+    drain: ManuallyDrop<Drain<'a, T>>,
+    array: NonNull<HoleArray<T, N>>,
+    pending: Option<T>,
+    replace_with: I,
+}
+
+unsafe impl<T: Send, I: Iterator<Item = T> + Send, const N: usize> Send
+    for ArraySplice<'_, T, I, N>
+{
+}
+unsafe impl<T: Sync, I: Iterator<Item = T> + Sync, const N: usize> Sync
+    for ArraySplice<'_, T, I, N>
+{
+}
+
+impl<T: fmt::Debug, I: Iterator<Item = T>, const N: usize> fmt::Debug for ArraySplice<'_, T, I, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("ArraySplice")
+            .field(&self.drain.as_slice())
+            .finish()
+    }
+}
+
+impl<T, I: Iterator<Item = T>, const N: usize> Iterator for ArraySplice<'_, T, I, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.drain.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.drain.size_hint()
+    }
+}
+
+impl<T, I: Iterator<Item = T>, const N: usize> DoubleEndedIterator for ArraySplice<'_, T, I, N> {
+    fn next_back(&mut self) -> Option<T> {
+        self.drain.next_back()
+    }
+}
+
+impl<T, I: Iterator<Item = T>, const N: usize> ExactSizeIterator for ArraySplice<'_, T, I, N> {}
+
+impl<T, I: Iterator<Item = T>, const N: usize> FusedIterator for ArraySplice<'_, T, I, N> {}
+
+impl<T, I: Iterator<Item = T>, const N: usize> Drop for ArraySplice<'_, T, I, N> {
+    fn drop(&mut self) {
+        unsafe { ManuallyDrop::drop(&mut self.drain) };
+        if let Some(value) = self.pending.take() {
+            let array = unsafe { self.array.as_mut() };
+            let mut values = iter::once(value).chain(&mut self.replace_with);
+            // `splice` checked that the values fit up to the upper bound of the
+            // `size_hint`, those beyond it are dropped
+            array.push_all(&mut values);
+        }
+    }
+}
+
+impl<T, const N: usize> IntoIterator for HoleArray<T, N> {
+    type Item = T;
+    type IntoIter = ArrayIntoIter<T, N>;
+
+    fn into_iter(self) -> ArrayIntoIter<T, N> {
+        ArrayIntoIter::new(self)
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a HoleArray<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut HoleArray<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use rand::{thread_rng, Rng};
+    use std::prelude::v1::*;
+    use std::rc::Rc;
+
+    // Hands out the values either with their exact length or with a lower
+    // bound of zero, so that an overflow is found before or while pushing
+    fn values_iter<'a, T: 'a>(values: Vec<T>, exact: bool) -> Box<dyn Iterator<Item = T> + 'a> {
+        if exact {
+            Box::new(values.into_iter())
+        } else {
+            Box::new(values.into_iter().filter(|_| true))
+        }
+    }
+
+    // The number of values that get pushed into a `HoleArray<_, 8>` of length
+    // `len` from a `values_iter` of `count` values
+    fn fitting(len: usize, count: usize, exact: bool) -> usize {
+        if exact && len + count > 8 {
+            0
+        } else {
+            count.min(8 - len)
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        let mut rng = thread_rng();
+        let value = Rc::new(());
+        for _ in 0..1000 {
+            let mut hole_array = HoleArray::<(u8, Rc<()>), 8>::new();
+            let mut model = Model::new();
+
+            for _ in 0..100 {
+                let len = model.len();
+                assert_eq!(Rc::strong_count(&value), 1 + 2 * len);
+                let new_value = (rng.gen::<u8>(), value.clone());
+                let new_values = (0..rng.gen::<usize>() % 4)
+                    .map(|_| (rng.gen::<u8>(), value.clone()))
+                    .collect::<Vec<_>>();
+                match rng.gen::<u64>() % 12 {
+                    0 => match hole_array.push_before_hole(new_value.clone()) {
+                        Ok(()) => model.before_hole.push(new_value),
+                        Err(rejected) => assert_eq!((len, rejected), (8, new_value)),
+                    },
+                    1 => match hole_array.push_after_hole(new_value.clone()) {
+                        Ok(()) => model.push_after_hole(new_value),
+                        Err(rejected) => assert_eq!((len, rejected), (8, new_value)),
+                    },
+                    2 => assert_eq!(hole_array.pop_before_hole(), model.before_hole.pop()),
+                    3 => assert_eq!(hole_array.pop_after_hole(), model.pop_after_hole()),
+                    4 => {
+                        let hole_position = model.before_hole.len();
+                        let amount = rng.gen::<usize>() % (hole_position + 1);
+                        let drained = hole_array.drain_before_hole(amount).collect::<Vec<_>>();
+                        assert_eq!(drained, model.drain(hole_position - amount, hole_position));
+                    }
+                    5 => {
+                        let hole_position = model.before_hole.len();
+                        let amount = rng.gen::<usize>() % (model.after_hole.len() + 1);
+                        let drained = model.drain(hole_position, hole_position + amount);
+                        assert!(hole_array.drain_after_hole(amount).eq(drained));
+                    }
+                    6 => {
+                        let index = rng.gen::<usize>() % (len + 1);
+                        let result = hole_array.insert(index, new_value.clone());
+                        if len == 8 {
+                            assert_eq!(result, Err(new_value));
+                        } else {
+                            assert_eq!(result, Ok(()));
+                            model.set_hole_position(index);
+                            model.before_hole.push(new_value);
+                        }
+                    }
+                    7 => {
+                        let exact = rng.gen::<bool>();
+                        let result =
+                            hole_array.extend_before_hole(values_iter(new_values.clone(), exact));
+                        let fits = fitting(len, new_values.len(), exact);
+                        match result {
+                            Ok(()) => assert_eq!(fits, new_values.len()),
+                            Err(rest) => {
+                                assert!(fits < new_values.len());
+                                assert!(rest.eq(new_values[fits..].iter().cloned()));
+                            }
+                        }
+                        model.before_hole.extend_from_slice(&new_values[..fits]);
+                    }
+                    8 => {
+                        let fits = len + new_values.len() <= 8;
+                        if rng.gen::<bool>() {
+                            let result = hole_array.extend_after_hole(new_values.clone());
+                            assert_eq!(result.is_ok(), fits);
+                            if let Err(rest) = result {
+                                assert!(rest.eq(new_values.iter().cloned()));
+                            }
+                        } else {
+                            let result = hole_array.extend_from_slice_after_hole(&new_values);
+                            let err = Err(&new_values[..]);
+                            assert_eq!(result, if fits { Ok(()) } else { err });
+                        }
+                        if fits {
+                            model.after_hole.splice(0..0, new_values);
+                        }
+                    }
+                    9 => {
+                        let (start, end) = random_range(&mut rng, len);
+                        assert!(hole_array.drain(start..end).eq(model.drain(start, end)));
+                    }
+                    10 => {
+                        let (start, end) = random_range(&mut rng, len);
+                        let exact = rng.gen::<bool>();
+                        let result =
+                            hole_array.splice(start..end, values_iter(new_values.clone(), exact));
+                        // The removed values make room for the replacement
+                        let fits = len - (end - start) + new_values.len() <= 8;
+                        match result {
+                            Ok(drained) => {
+                                assert!(fits);
+                                assert!(drained.eq(model.drain(start, end)));
+                                model.before_hole.extend_from_slice(&new_values);
+                            }
+                            Err(rest) => {
+                                assert!(!fits);
+                                assert!(rest.eq(new_values.iter().cloned()));
+                            }
+                        }
+                    }
+                    _ => {
+                        let position = rng.gen::<usize>() % (len + 2);
+                        let result = hole_array.try_set_hole_position(position);
+                        assert_eq!(result, bounds_result(position, len));
+                        if result.is_ok() {
+                            model.set_hole_position(position);
+                        }
+                    }
+                }
+
+                assert_eq!(hole_array.as_slices(), model.as_slices());
+                let values = model.values();
+                assert_eq!(hole_array, values[..]);
+                assert_values!(hole_array, values);
+                assert_eq!(hole_array.is_full(), values.len() == 8);
+                assert_eq!(hole_array.gap_len(), 8 - values.len());
+                assert_eq!(hole_array.get_mut(values.len()), None);
+                assert_eq!(hole_array.get_mut(usize::MAX), None);
+                assert_eq!(hole_array.last(), values.last());
+                let start = values.len() / 2;
+                assert_eq!(hole_array.range(start..), values[start..]);
+                assert_eq!(hole_array.range_mut(..start), values[..start]);
+                assert_search!(hole_array, values, &mut rng);
+                let bytes = values.iter().map(|value| value.0).collect::<Vec<_>>();
+                let mut byte_array = HoleArray::<u8, 8>::new();
+                let _ = byte_array.extend_before_hole(bytes.iter().copied());
+                byte_array.set_hole_position(model.before_hole.len());
+                assert_search!(byte_array, bytes, &mut rng, bytes);
+
+                let clone = hole_array.clone();
+                assert_eq!(clone.as_slices(), hole_array.as_slices());
+                assert_into_iter!(clone.into_iter(), values, "ArrayIntoIter");
+                // Dropping a partly consumed iterator drops the rest
+                let mut into_iter = hole_array.clone().into_iter();
+                into_iter.next();
+            }
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
@@ -0,0 +1,139 @@
+use core::fmt;
+
+use allocator_api2::alloc::{Allocator, Global};
+
+use crate::HoleVec;
+
+// A read-only position between two values of a `HoleVec`. It starts out at
+// the hole but moves independently of it.
+pub struct Cursor<'a, T, A: Allocator = Global> {
+    hole_vec: &'a HoleVec<T, A>,
+    position: usize,
+}
+
+impl<'a, T, A: Allocator> Cursor<'a, T, A> {
+    pub(crate) fn new(hole_vec: &'a HoleVec<T, A>, position: usize) -> Self {
+        Self { hole_vec, position }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn move_next(&mut self) -> bool {
+        let moved = self.position < self.hole_vec.len();
+        self.position += moved as usize;
+        moved
+    }
+
+    pub fn move_prev(&mut self) -> bool {
+        let moved = self.position > 0;
+        self.position -= moved as usize;
+        moved
+    }
+
+    pub fn seek(&mut self, position: usize) {
+        assert!(position <= self.hole_vec.len());
+        self.position = position;
+    }
+
+    pub fn peek_next(&self) -> Option<&'a T> {
+        self.hole_vec.get(self.position)
+    }
+
+    pub fn peek_prev(&self) -> Option<&'a T> {
+        let position = self.position.checked_sub(1)?;
+        self.hole_vec.get(position)
+    }
+
+    pub fn as_hole_vec(&self) -> &'a HoleVec<T, A> {
+        self.hole_vec
+    }
+}
+
+impl<T, A: Allocator> Clone for Cursor<'_, T, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, A: Allocator> Copy for Cursor<'_, T, A> {}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for Cursor<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Cursor")
+            .field(&self.hole_vec)
+            .field(&self.position)
+            .finish()
+    }
+}
+
+// A cursor that owns the hole of a `HoleVec`, so that its position is always
+// the hole position and edits at the cursor are cheap.
+pub struct CursorMut<'a, T, A: Allocator = Global> {
+    hole_vec: &'a mut HoleVec<T, A>,
+}
+
+impl<'a, T, A: Allocator> CursorMut<'a, T, A> {
+    pub(crate) fn new(hole_vec: &'a mut HoleVec<T, A>) -> Self {
+        Self { hole_vec }
+    }
+
+    pub fn position(&self) -> usize {
+        self.hole_vec.len_before_hole()
+    }
+
+    pub fn move_next(&mut self) -> bool {
+        let moved = self.hole_vec.len_after_hole() > 0;
+        self.hole_vec.move_hole_right(moved as usize);
+        moved
+    }
+
+    pub fn move_prev(&mut self) -> bool {
+        let moved = self.hole_vec.len_before_hole() > 0;
+        self.hole_vec.move_hole_left(moved as usize);
+        moved
+    }
+
+    pub fn seek(&mut self, position: usize) {
+        self.hole_vec.set_hole_position(position);
+    }
+
+    pub fn peek_next(&mut self) -> Option<&mut T> {
+        self.hole_vec.as_mut_slices_after_hole().first_mut()
+    }
+
+    pub fn peek_prev(&mut self) -> Option<&mut T> {
+        self.hole_vec.as_mut_slices_before_hole().last_mut()
+    }
+
+    // Inserts `value` before the cursor, so that the cursor ends up after it
+    pub fn insert(&mut self, value: T) {
+        self.hole_vec.push_before_hole(value);
+    }
+
+    pub fn remove_next(&mut self) -> Option<T> {
+        self.hole_vec.pop_after_hole()
+    }
+
+    pub fn remove_prev(&mut self) -> Option<T> {
+        self.hole_vec.pop_before_hole()
+    }
+
+    pub fn as_cursor(&self) -> Cursor<'_, T, A> {
+        self.hole_vec.cursor()
+    }
+
+    pub fn as_hole_vec(&self) -> &HoleVec<T, A> {
+        self.hole_vec
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for CursorMut<'_, T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CursorMut")
+            .field(&self.hole_vec)
+            .field(&self.position())
+            .finish()
+    }
+}
@@ -0,0 +1,68 @@
+use core::alloc::Layout;
+use core::error::Error;
+use core::fmt;
+use core::ops::{Bound, RangeBounds};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HoleVecError {
+    // The requested amount or position exceeds what is available
+    OutOfBounds { requested: usize, available: usize },
+    // The required capacity does not fit in a valid layout
+    CapacityOverflow,
+    // The allocator failed to provide memory for the given layout
+    AllocError { layout: Layout },
+    // The position would split a UTF-8 encoded character
+    NotCharBoundary { position: usize },
+}
+
+impl fmt::Display for HoleVecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HoleVecError::OutOfBounds {
+                requested,
+                available,
+            } => write!(
+                f,
+                "out of bounds: requested {} but only {} available",
+                requested, available
+            ),
+            HoleVecError::CapacityOverflow => write!(f, "capacity overflow"),
+            HoleVecError::AllocError { layout } => {
+                write!(f, "memory allocation of {} bytes failed", layout.size())
+            }
+            HoleVecError::NotCharBoundary { position } => {
+                write!(f, "position {} is not a char boundary", position)
+            }
+        }
+    }
+}
+
+impl Error for HoleVecError {}
+
+pub(crate) fn check_bounds(requested: usize, available: usize) -> Result<(), HoleVecError> {
+    if requested <= available {
+        Ok(())
+    } else {
+        Err(HoleVecError::OutOfBounds {
+            requested,
+            available,
+        })
+    }
+}
+
+// Resolves `range` against `len`, panicking if it is out of bounds
+pub(crate) fn range_bounds<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflow"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflow"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(start <= end, "range start {} is after end {}", start, end);
+    assert!(end <= len, "range end {} is out of bounds", end);
+    (start, end)
+}
@@ -0,0 +1,395 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use allocator_api2::alloc::{Allocator, Global};
+
+use crate::HoleVec;
+
+// An edit of a `HoleVec` that can be applied to get the inverse edit back.
+#[derive(Clone, Debug)]
+enum Edit<T> {
+    PushBeforeHole(Vec<T>),
+    PushAfterHole(Vec<T>),
+    PopBeforeHole(usize),
+    PopAfterHole(usize),
+    MoveHoleRight(usize),
+    MoveHoleLeft(usize),
+}
+
+impl<T> Edit<T> {
+    fn apply<A: Allocator>(self, vec: &mut HoleVec<T, A>) -> Self {
+        match self {
+            Edit::PushBeforeHole(values) => {
+                let amount = values.len();
+                vec.extend_before_hole(values);
+                Edit::PopBeforeHole(amount)
+            }
+            Edit::PushAfterHole(values) => {
+                let amount = values.len();
+                vec.extend_after_hole(values);
+                Edit::PopAfterHole(amount)
+            }
+            Edit::PopBeforeHole(amount) => {
+                Edit::PushBeforeHole(vec.drain_before_hole(amount).collect())
+            }
+            Edit::PopAfterHole(amount) => {
+                Edit::PushAfterHole(vec.drain_after_hole(amount).collect())
+            }
+            Edit::MoveHoleRight(amount) => {
+                vec.move_hole_right(amount);
+                Edit::MoveHoleLeft(amount)
+            }
+            Edit::MoveHoleLeft(amount) => {
+                vec.move_hole_left(amount);
+                Edit::MoveHoleRight(amount)
+            }
+        }
+    }
+}
+
+// A `HoleVec` that records its edits so they can be undone and redone.
+//
+// Every edit made outside a transaction becomes its own undo entry, except
+// that runs of single-value pushes on the same side of the hole are coalesced
+// into one. Each entry stores the edits that revert it, which are applied
+// last to first.
+pub struct History<T, A: Allocator = Global> {
+    vec: HoleVec<T, A>,
+    undo: Vec<Vec<Edit<T>>>,
+    redo: Vec<Vec<Edit<T>>>,
+    // The amount of unfinished `begin_transaction` calls
+    transaction_depth: usize,
+    // Whether the last undo entry is a run of pushes that may still grow
+    coalescing: bool,
+}
+
+impl<T, A: Allocator> History<T, A> {
+    pub fn new(vec: HoleVec<T, A>) -> Self {
+        Self {
+            vec,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            transaction_depth: 0,
+            coalescing: false,
+        }
+    }
+
+    pub fn as_hole_vec(&self) -> &HoleVec<T, A> {
+        &self.vec
+    }
+
+    pub fn into_hole_vec(self) -> HoleVec<T, A> {
+        self.vec
+    }
+
+    pub fn push_before_hole(&mut self, value: T) {
+        self.vec.push_before_hole(value);
+        self.record(Edit::PopBeforeHole(1));
+    }
+
+    pub fn push_after_hole(&mut self, value: T) {
+        self.vec.push_after_hole(value);
+        self.record(Edit::PopAfterHole(1));
+    }
+
+    pub fn move_hole_right(&mut self, amount: usize) {
+        self.vec.move_hole_right(amount);
+        if amount != 0 {
+            self.record(Edit::MoveHoleLeft(amount));
+        }
+    }
+
+    pub fn move_hole_left(&mut self, amount: usize) {
+        self.vec.move_hole_left(amount);
+        if amount != 0 {
+            self.record(Edit::MoveHoleRight(amount));
+        }
+    }
+
+    pub fn set_hole_position(&mut self, position: usize) {
+        let hole_position = self.vec.len_before_hole();
+        if position >= hole_position {
+            self.move_hole_right(position - hole_position);
+        } else {
+            self.move_hole_left(hole_position - position);
+        }
+    }
+
+    // Groups all edits until the matching `commit_transaction` into a single
+    // undo entry. Transactions may be nested.
+    pub fn begin_transaction(&mut self) {
+        if self.transaction_depth == 0 {
+            self.undo.push(Vec::new());
+            self.coalescing = false;
+        }
+        self.transaction_depth += 1;
+    }
+
+    pub fn commit_transaction(&mut self) {
+        assert!(self.transaction_depth > 0, "no transaction to commit");
+        self.transaction_depth -= 1;
+        if self.transaction_depth == 0 && self.undo.last().is_some_and(Vec::is_empty) {
+            self.undo.pop();
+        }
+    }
+
+    // An open transaction that has not recorded any edits yet is not an undo
+    // entry, as committing it discards it
+    pub fn can_undo(&self) -> bool {
+        self.undo.iter().any(|edits| !edits.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    // Reverts the last undo entry, restoring both the values and the hole
+    // position. Any open transaction is committed first.
+    pub fn undo(&mut self) -> bool {
+        self.end_transactions();
+        match self.undo.pop() {
+            Some(edits) => {
+                let edits = self.apply(edits);
+                self.redo.push(edits);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn redo(&mut self) -> bool {
+        self.end_transactions();
+        match self.redo.pop() {
+            Some(edits) => {
+                let edits = self.apply(edits);
+                self.undo.push(edits);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Forgets all undo and redo entries. An open transaction stays open and
+    // collects the edits made from here on.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.coalescing = false;
+        if self.transaction_depth > 0 {
+            self.undo.push(Vec::new());
+        }
+    }
+
+    fn record(&mut self, edit: Edit<T>) {
+        self.redo.clear();
+        let continues_run = matches!(
+            (self.undo.last().and_then(|edits| edits.last()), &edit),
+            (Some(Edit::PopBeforeHole(_)), Edit::PopBeforeHole(1))
+                | (Some(Edit::PopAfterHole(_)), Edit::PopAfterHole(1))
+        );
+        if self.transaction_depth == 0 && !(continues_run && self.coalescing) {
+            self.coalescing = matches!(edit, Edit::PopBeforeHole(1) | Edit::PopAfterHole(1));
+            self.undo.push(vec![edit]);
+            return;
+        }
+
+        let edits = self.undo.last_mut().expect("BUG");
+        match edits.last_mut() {
+            Some(Edit::PopBeforeHole(amount) | Edit::PopAfterHole(amount)) if continues_run => {
+                *amount += 1
+            }
+            _ => edits.push(edit),
+        }
+    }
+
+    fn apply(&mut self, edits: Vec<Edit<T>>) -> Vec<Edit<T>> {
+        edits
+            .into_iter()
+            .rev()
+            .map(|edit| edit.apply(&mut self.vec))
+            .collect()
+    }
+
+    // Commits any open transaction and ends the current run of pushes
+    fn end_transactions(&mut self) {
+        self.coalescing = false;
+        while self.transaction_depth > 0 {
+            self.commit_transaction();
+        }
+    }
+}
+
+impl<T: Clone, A: Allocator> History<T, A> {
+    pub fn pop_before_hole(&mut self) -> Option<T> {
+        let value = self.vec.pop_before_hole()?;
+        self.record(Edit::PushBeforeHole(vec![value.clone()]));
+        Some(value)
+    }
+
+    pub fn pop_after_hole(&mut self) -> Option<T> {
+        let value = self.vec.pop_after_hole()?;
+        self.record(Edit::PushAfterHole(vec![value.clone()]));
+        Some(value)
+    }
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(HoleVec::new())
+    }
+}
+
+impl<T, A: Allocator> From<HoleVec<T, A>> for History<T, A> {
+    fn from(vec: HoleVec<T, A>) -> Self {
+        Self::new(vec)
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for History<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("History")
+            .field("vec", &self.vec)
+            .field("undo", &self.undo.len())
+            .field("redo", &self.redo.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+    use std::prelude::v1::*;
+
+    fn state(history: &History<u8>) -> (Vec<u8>, usize) {
+        let vec = history.as_hole_vec();
+        (vec.iter().copied().collect(), vec.len_before_hole())
+    }
+
+    #[test]
+    fn clear_in_transaction() {
+        let mut history = History::default();
+        history.begin_transaction();
+        history.push_before_hole(1);
+        history.clear();
+        assert!(!history.can_undo());
+        history.push_before_hole(2);
+        history.commit_transaction();
+        assert!(history.undo());
+        assert_eq!(state(&history), (vec![1], 1));
+        assert!(!history.undo());
+    }
+
+    #[test]
+    fn it_works() {
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut history = History::default();
+            let mut undo = Vec::new();
+            let mut redo = Vec::new();
+            // The state when the open transaction began and whether it has
+            // recorded any edits yet
+            let mut transaction = None;
+            let mut depth = 0;
+            // The side of the run of pushes that the last entry holds
+            let mut coalescing = None;
+
+            for _ in 0..1000 {
+                let before = state(&history);
+                let value = rng.gen::<u8>();
+                let edit = match rng.gen::<u64>() % 9 {
+                    0 | 1 => {
+                        history.push_before_hole(value);
+                        Some(Some(false))
+                    }
+                    2 => {
+                        history.push_after_hole(value);
+                        Some(Some(true))
+                    }
+                    3 => {
+                        let expected = before.0[..before.1].last().copied();
+                        assert_eq!(history.pop_before_hole(), expected);
+                        expected.map(|_| None)
+                    }
+                    4 => {
+                        let expected = before.0.get(before.1).copied();
+                        assert_eq!(history.pop_after_hole(), expected);
+                        expected.map(|_| None)
+                    }
+                    5 => {
+                        let position = rng.gen::<usize>() % (before.0.len() + 1);
+                        history.set_hole_position(position);
+                        Some(None).filter(|_| position != before.1)
+                    }
+                    6 => {
+                        if depth == 0 {
+                            transaction = Some((before.clone(), false));
+                        }
+                        depth += 1;
+                        history.begin_transaction();
+                        None
+                    }
+                    7 => {
+                        if depth > 0 {
+                            depth -= 1;
+                            history.commit_transaction();
+                            if depth == 0 {
+                                if let Some((start, true)) = transaction.take() {
+                                    undo.push(start);
+                                }
+                                coalescing = None;
+                            }
+                        }
+                        None
+                    }
+                    _ => {
+                        if let Some((start, true)) = transaction.take() {
+                            undo.push(start);
+                        }
+                        depth = 0;
+                        coalescing = None;
+                        let (from, to) = if rng.gen::<bool>() {
+                            assert_eq!(history.undo(), !undo.is_empty());
+                            (&mut undo, &mut redo)
+                        } else {
+                            assert_eq!(history.redo(), !redo.is_empty());
+                            (&mut redo, &mut undo)
+                        };
+                        if let Some(expected) = from.pop() {
+                            assert_eq!(state(&history), expected);
+                            to.push(before.clone());
+                        }
+                        None
+                    }
+                };
+
+                if let Some(side) = edit {
+                    redo.clear();
+                    match &mut transaction {
+                        Some((_, changed)) => *changed = true,
+                        None => {
+                            if side.is_none() || side != coalescing {
+                                undo.push(before);
+                            }
+                            coalescing = side;
+                        }
+                    }
+                }
+                assert_eq!(
+                    history.can_undo(),
+                    !undo.is_empty() || matches!(transaction, Some((_, true)))
+                );
+                assert_eq!(history.can_redo(), !redo.is_empty());
+            }
+
+            if let Some((start, true)) = transaction {
+                undo.push(start);
+            }
+            while history.undo() {}
+            if let Some(initial) = undo.first() {
+                assert_eq!(&state(&history), initial);
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use core::fmt;
+use core::iter::FusedIterator;
+use core::ops::Range;
+use core::ptr;
+use core::slice;
+
+use allocator_api2::alloc::{Allocator, Global};
+
+use crate::raw::RawBuf;
+use crate::{HoleVec, Iter, IterMut};
+
+// Owns the buffer of a `HoleVec` and reads the values out of it one by one,
+// from both of the ranges they were stored in.
+pub struct IntoIter<T, A: Allocator = Global> {
+    buf: RawBuf<T, A>,
+    before_hole: Range<usize>,
+    after_hole: Range<usize>,
+}
+
+impl<T, A: Allocator> IntoIter<T, A> {
+    pub(crate) fn new(hole_vec: HoleVec<T, A>) -> Self {
+        let (buf, before_hole, after_hole) = hole_vec.into_raw_parts();
+        Self {
+            buf,
+            before_hole,
+            after_hole,
+        }
+    }
+
+    pub(crate) fn as_slices(&self) -> (&[T], &[T]) {
+        let slice = |range: &Range<usize>| unsafe {
+            slice::from_raw_parts(self.buf.ptr().add(range.start), range.len())
+        };
+        (slice(&self.before_hole), slice(&self.after_hole))
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for IntoIter<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (a0, a1) = self.as_slices();
+        f.debug_tuple("IntoIter")
+            .field(&Iter::new((a0, a1)))
+            .finish()
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for IntoIter<T, A> {
+    fn clone(&self) -> Self {
+        let (a0, a1) = self.as_slices();
+        let mut hole_vec = HoleVec::with_capacity_in(self.len(), self.buf.allocator().clone());
+        hole_vec.extend_from_slice_before_hole(a0);
+        hole_vec.extend_from_slice_before_hole(a1);
+        Self::new(hole_vec)
+    }
+}
+
+impl<T, A: Allocator> Iterator for IntoIter<T, A> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let index = self.before_hole.next().or_else(|| self.after_hole.next())?;
+        Some(unsafe { self.buf.ptr().add(index).read() })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<T, A: Allocator> DoubleEndedIterator for IntoIter<T, A> {
+    fn next_back(&mut self) -> Option<T> {
+        let index = self
+            .after_hole
+            .next_back()
+            .or_else(|| self.before_hole.next_back())?;
+        Some(unsafe { self.buf.ptr().add(index).read() })
+    }
+}
+
+impl<T, A: Allocator> ExactSizeIterator for IntoIter<T, A> {
+    fn len(&self) -> usize {
+        self.before_hole.len() + self.after_hole.len()
+    }
+}
+
+impl<T, A: Allocator> FusedIterator for IntoIter<T, A> {}
+
+impl<T, A: Allocator> Drop for IntoIter<T, A> {
+    fn drop(&mut self) {
+        for range in [&self.before_hole, &self.after_hole] {
+            unsafe {
+                ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                    self.buf.ptr().add(range.start),
+                    range.len(),
+                ));
+            }
+        }
+    }
+}
+
+impl<T, A: Allocator> IntoIterator for HoleVec<T, A> {
+    type Item = T;
+    type IntoIter = IntoIter<T, A>;
+
+    fn into_iter(self) -> IntoIter<T, A> {
+        IntoIter::new(self)
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a HoleVec<T, A> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, A: Allocator> IntoIterator for &'a mut HoleVec<T, A> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,90 @@
+use std::io::{self, BufRead, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+
+use allocator_api2::alloc::Allocator;
+
+use crate::HoleVec;
+
+// A `HoleVec<u8>` behaves like an editable in-memory file whose position is
+// the hole: writes insert before the hole and reads move it forward.
+
+impl<A: Allocator> HoleVec<u8, A> {
+    // The values as `IoSlice`s, ready for `Write::write_vectored`
+    pub fn as_io_slices(&self) -> [IoSlice<'_>; 2] {
+        let (a0, a1) = self.as_slices();
+        [IoSlice::new(a0), IoSlice::new(a1)]
+    }
+}
+
+impl<A: Allocator> Write for HoleVec<u8, A> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.extend_from_slice_before_hole(buf);
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> io::Result<usize> {
+        let len = bufs.iter().map(|buf| buf.len()).sum();
+        self.reserve(len);
+        for buf in bufs {
+            self.extend_from_slice_before_hole(buf);
+        }
+        Ok(len)
+    }
+
+    fn write_all(&mut self, buf: &[u8]) -> io::Result<()> {
+        self.extend_from_slice_before_hole(buf);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<A: Allocator> Read for HoleVec<u8, A> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.len_after_hole());
+        buf[..len].copy_from_slice(&self.as_slices_after_hole()[..len]);
+        self.move_hole_right(len);
+        Ok(len)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let mut after_hole = self.as_slices_after_hole();
+        for buf in bufs {
+            let len = buf.len().min(after_hole.len());
+            let (head, tail) = after_hole.split_at(len);
+            buf[..len].copy_from_slice(head);
+            after_hole = tail;
+        }
+        let len = self.len_after_hole() - after_hole.len();
+        self.move_hole_right(len);
+        Ok(len)
+    }
+}
+
+impl<A: Allocator> BufRead for HoleVec<u8, A> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        Ok(self.as_slices_after_hole())
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.move_hole_right(amount);
+    }
+}
+
+impl<A: Allocator> Seek for HoleVec<u8, A> {
+    // Seeking past either end is an error, as there is nothing to fill the
+    // space between the end and the new position with
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        let position = match position {
+            SeekFrom::Start(position) => Some(position),
+            SeekFrom::End(offset) => (self.len() as u64).checked_add_signed(offset),
+            SeekFrom::Current(offset) => (self.len_before_hole() as u64).checked_add_signed(offset),
+        };
+        let position = position
+            .filter(|&position| position <= self.len() as u64)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "seek out of bounds"))?;
+        self.set_hole_position(position as usize);
+        Ok(position)
+    }
+}
@@ -0,0 +1,182 @@
+use core::fmt;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::ptr::{self, NonNull};
+use core::slice;
+
+pub struct Iter<'a, T> {
+    slices: [slice::Iter<'a, T>; 2],
+}
+
+impl<'a, T> Iter<'a, T> {
+    pub(crate) fn new(slices: (&'a [T], &'a [T])) -> Self {
+        Self {
+            slices: [slices.0.iter(), slices.1.iter()],
+        }
+    }
+}
+
+impl<T> Clone for Iter<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            slices: self.slices.clone(),
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Iter<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.clone()).finish()
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.slices.iter_mut().find_map(|slice| slice.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
+    fn next_back(&mut self) -> Option<&'a T> {
+        self.slices
+            .iter_mut()
+            .rev()
+            .find_map(|slice| slice.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for Iter<'_, T> {
+    fn len(&self) -> usize {
+        self.slices.iter().map(|slice| slice.len()).sum()
+    }
+}
+
+impl<T> FusedIterator for Iter<'_, T> {}
+
+pub struct IterMut<'a, T> {
+    slices: [slice::IterMut<'a, T>; 2],
+}
+
+impl<'a, T> IterMut<'a, T> {
+    pub(crate) fn new(slices: (&'a mut [T], &'a mut [T])) -> Self {
+        Self {
+            slices: [slices.0.iter_mut(), slices.1.iter_mut()],
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for IterMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.slices.iter().flat_map(|slice| slice.as_slice()))
+            .finish()
+    }
+}
+
+impl<'a, T> Iterator for IterMut<'a, T> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<&'a mut T> {
+        self.slices.iter_mut().find_map(|slice| slice.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for IterMut<'a, T> {
+    fn next_back(&mut self) -> Option<&'a mut T> {
+        self.slices
+            .iter_mut()
+            .rev()
+            .find_map(|slice| slice.next_back())
+    }
+}
+
+impl<T> ExactSizeIterator for IterMut<'_, T> {
+    fn len(&self) -> usize {
+        self.slices.iter().map(|slice| slice.len()).sum()
+    }
+}
+
+impl<T> FusedIterator for IterMut<'_, T> {}
+
+// The values being drained have already been moved into the hole, so all
+// that is left to do is to read them out or drop them.
+pub struct Drain<'a, T> {
+    ptr: NonNull<T>,
+    len: usize,
+    _marker: PhantomData<&'a mut T>,
+}
+
+unsafe impl<T: Send> Send for Drain<'_, T> {}
+unsafe impl<T: Sync> Sync for Drain<'_, T> {}
+
+impl<'a, T> Drain<'a, T> {
+    // Safety: `ptr` must point to `len` initialized values that are owned by
+    // the `Drain` and stay valid for `'a`.
+    pub(crate) unsafe fn new(ptr: *mut T, len: usize) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            len,
+            _marker: PhantomData,
+        }
+    }
+
+    pub(crate) fn as_slice(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Drain<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Drain").field(&self.as_slice()).finish()
+    }
+}
+
+impl<T> Iterator for Drain<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        (self.len > 0).then(|| unsafe {
+            let value = self.ptr.as_ptr().read();
+            self.ptr = NonNull::new_unchecked(self.ptr.as_ptr().add(1));
+            self.len -= 1;
+            value
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T> DoubleEndedIterator for Drain<'_, T> {
+    fn next_back(&mut self) -> Option<T> {
+        (self.len > 0).then(|| unsafe {
+            self.len -= 1;
+            self.ptr.as_ptr().add(self.len).read()
+        })
+    }
+}
+
+impl<T> ExactSizeIterator for Drain<'_, T> {}
+
+impl<T> FusedIterator for Drain<'_, T> {}
+
+impl<T> Drop for Drain<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(self.ptr.as_ptr(), self.len));
+        }
+    }
+}
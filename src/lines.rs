@@ -0,0 +1,88 @@
+use alloc::vec::Vec;
+
+// The byte offsets of the newlines in a `HoleString`, split at the hole like
+// the text itself so that edits at the hole only touch the end of either list.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct LineIndex {
+    // Offsets from the start of the text, in increasing order
+    before_hole: Vec<usize>,
+    // Offsets from the end of the text, in increasing order, so that the
+    // newline closest to the hole comes last
+    after_hole: Vec<usize>,
+}
+
+impl LineIndex {
+    pub(crate) fn newline_count(&self) -> usize {
+        self.before_hole.len() + self.after_hole.len()
+    }
+
+    // The offset of the `index`th newline
+    pub(crate) fn newline(&self, index: usize, len: usize) -> Option<usize> {
+        match index.checked_sub(self.before_hole.len()) {
+            None => Some(self.before_hole[index]),
+            Some(index) => {
+                let index = self.after_hole.len().checked_sub(index + 1)?;
+                Some(len - self.after_hole[index])
+            }
+        }
+    }
+
+    // The amount of newlines before `offset`
+    pub(crate) fn newlines_before(&self, offset: usize, hole_position: usize, len: usize) -> usize {
+        if offset <= hole_position {
+            self.before_hole
+                .partition_point(|&position| position < offset)
+        } else {
+            let end_offset = len - offset;
+            self.before_hole.len() + self.after_hole.len()
+                - self.after_hole.partition_point(|&e| e <= end_offset)
+        }
+    }
+
+    pub(crate) fn inserted_before_hole(&mut self, hole_position: usize, bytes: &[u8]) {
+        self.before_hole
+            .extend(newlines(bytes).map(|index| hole_position + index));
+    }
+
+    pub(crate) fn inserted_after_hole(&mut self, after_hole: usize, bytes: &[u8]) {
+        let len = after_hole + bytes.len();
+        self.after_hole
+            .extend(newlines(bytes).rev().map(|index| len - index));
+    }
+
+    pub(crate) fn removed_before_hole(&mut self, hole_position: usize) {
+        while self.before_hole.last().is_some_and(|&p| p >= hole_position) {
+            self.before_hole.pop();
+        }
+    }
+
+    pub(crate) fn removed_after_hole(&mut self, after_hole: usize) {
+        while self.after_hole.last().is_some_and(|&e| e > after_hole) {
+            self.after_hole.pop();
+        }
+    }
+
+    pub(crate) fn moved_right(&mut self, hole_position: usize, after_hole: usize) {
+        let len = hole_position + after_hole;
+        while let Some(&e) = self.after_hole.last().filter(|&&e| e > after_hole) {
+            self.after_hole.pop();
+            self.before_hole.push(len - e);
+        }
+    }
+
+    pub(crate) fn moved_left(&mut self, hole_position: usize, after_hole: usize) {
+        let len = hole_position + after_hole;
+        while let Some(&p) = self.before_hole.last().filter(|&&p| p >= hole_position) {
+            self.before_hole.pop();
+            self.after_hole.push(len - p);
+        }
+    }
+}
+
+fn newlines(bytes: &[u8]) -> impl DoubleEndedIterator<Item = usize> + '_ {
+    bytes
+        .iter()
+        .enumerate()
+        .filter(|&(_, &byte)| byte == b'\n')
+        .map(|(index, _)| index)
+}
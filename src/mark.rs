@@ -0,0 +1,251 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+// A position in a `HoleVec` that stays attached to the surrounding values as
+// the `HoleVec` is edited.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Mark(u64);
+
+// Decides which side of a mark values inserted at its position end up on.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Gravity {
+    // The mark stays in front of inserted values
+    Left,
+    // The mark moves along behind inserted values
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Side {
+    BeforeHole,
+    AfterHole,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Slot {
+    gravity: Gravity,
+    side: Side,
+    // Distance from the start for marks before the hole and from the end for
+    // marks after it
+    offset: usize,
+}
+
+// Marks before the hole are stored relative to the start of the `HoleVec` and
+// marks after the hole relative to its end, so that inserting and removing
+// values at the hole leaves almost all of them untouched. A mark at the hole
+// itself is stored on the side given by its gravity.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Marks {
+    next_id: u64,
+    slots: BTreeMap<u64, Slot>,
+    before_hole: BTreeSet<(usize, u64)>,
+    after_hole: BTreeSet<(usize, u64)>,
+}
+
+impl Marks {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+
+    pub(crate) fn add(
+        &mut self,
+        position: usize,
+        gravity: Gravity,
+        hole_position: usize,
+        after_hole: usize,
+    ) -> Mark {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.place(id, gravity, position, hole_position, after_hole);
+        Mark(id)
+    }
+
+    pub(crate) fn remove(&mut self, mark: Mark, len: usize) -> Option<usize> {
+        let slot = self.slots.remove(&mark.0)?;
+        self.set_mut(slot.side).remove(&(slot.offset, mark.0));
+        Some(Self::slot_position(&slot, len))
+    }
+
+    pub(crate) fn position(&self, mark: Mark, len: usize) -> Option<usize> {
+        self.slots
+            .get(&mark.0)
+            .map(|slot| Self::slot_position(slot, len))
+    }
+
+    pub(crate) fn gravity(&self, mark: Mark) -> Option<Gravity> {
+        self.slots.get(&mark.0).map(|slot| slot.gravity)
+    }
+
+    // Updates the marks after the hole moved right by `amount`
+    pub(crate) fn moved_right(&mut self, amount: usize, hole_position: usize, after_hole: usize) {
+        let len = hole_position + after_hole;
+        for (id, slot) in self.take(Side::AfterHole, after_hole, after_hole + amount) {
+            let position = Self::slot_position(&slot, len);
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after the hole moved left by `amount`
+    pub(crate) fn moved_left(&mut self, amount: usize, hole_position: usize, after_hole: usize) {
+        let len = hole_position + after_hole;
+        for (id, slot) in self.take(Side::BeforeHole, hole_position, hole_position + amount) {
+            let position = Self::slot_position(&slot, len);
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after `amount` values right before the hole were
+    // removed. Marks inside the removed range collapse onto the hole.
+    pub(crate) fn removed_before_hole(
+        &mut self,
+        amount: usize,
+        hole_position: usize,
+        after_hole: usize,
+    ) {
+        for (id, slot) in self.take(Side::BeforeHole, hole_position, hole_position + amount) {
+            self.place(id, slot.gravity, hole_position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after `amount` values right after the hole were
+    // removed. Marks inside the removed range collapse onto the hole.
+    pub(crate) fn removed_after_hole(
+        &mut self,
+        amount: usize,
+        hole_position: usize,
+        after_hole: usize,
+    ) {
+        for (id, slot) in self.take(Side::AfterHole, after_hole, after_hole + amount) {
+            self.place(id, slot.gravity, hole_position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after `amount` values were inserted at the start,
+    // before the hole. Marks at the start with left gravity stay there. Like
+    // the three functions below, this re-places every mark on its side of the
+    // hole, so it takes time linear in their amount.
+    pub(crate) fn inserted_front(
+        &mut self,
+        amount: usize,
+        hole_position: usize,
+        after_hole: usize,
+    ) {
+        for (id, slot) in self.take(Side::BeforeHole, 0, usize::MAX) {
+            let position = if slot.offset == 0 && slot.gravity == Gravity::Left {
+                0
+            } else {
+                slot.offset + amount
+            };
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after `amount` values were inserted at the end, after
+    // the hole. Marks at the end with right gravity stay there.
+    pub(crate) fn inserted_back(&mut self, amount: usize, hole_position: usize, after_hole: usize) {
+        let len = hole_position + after_hole;
+        for (id, slot) in self.take(Side::AfterHole, 0, usize::MAX) {
+            let position = if slot.offset == 0 && slot.gravity == Gravity::Right {
+                len
+            } else {
+                Self::slot_position(&slot, len - amount)
+            };
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after `amount` values were removed from the start,
+    // before the hole. Marks inside the removed range collapse onto the start.
+    pub(crate) fn removed_front(&mut self, amount: usize, hole_position: usize, after_hole: usize) {
+        for (id, slot) in self.take(Side::BeforeHole, 0, usize::MAX) {
+            let position = slot.offset.saturating_sub(amount);
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    // Updates the marks after `amount` values were removed from the end, after
+    // the hole. Marks inside the removed range collapse onto the end.
+    pub(crate) fn removed_back(&mut self, amount: usize, hole_position: usize, after_hole: usize) {
+        let len = hole_position + after_hole;
+        for (id, slot) in self.take(Side::AfterHole, 0, usize::MAX) {
+            let position = Self::slot_position(&slot, len + amount).min(len);
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    // Moves the marks with left gravity among the `amount` values right after
+    // the hole onto the hole, as if those values had been removed. Used when
+    // values are inserted before a range that is about to be removed.
+    pub(crate) fn collapse_left_gravity(
+        &mut self,
+        amount: usize,
+        hole_position: usize,
+        after_hole: usize,
+    ) {
+        let start = after_hole - amount;
+        for (id, slot) in self.take(Side::AfterHole, start, after_hole) {
+            let position = if slot.gravity == Gravity::Left {
+                hole_position
+            } else {
+                hole_position + after_hole - slot.offset
+            };
+            self.place(id, slot.gravity, position, hole_position, after_hole);
+        }
+    }
+
+    fn slot_position(slot: &Slot, len: usize) -> usize {
+        match slot.side {
+            Side::BeforeHole => slot.offset,
+            Side::AfterHole => len - slot.offset,
+        }
+    }
+
+    fn set_mut(&mut self, side: Side) -> &mut BTreeSet<(usize, u64)> {
+        match side {
+            Side::BeforeHole => &mut self.before_hole,
+            Side::AfterHole => &mut self.after_hole,
+        }
+    }
+
+    fn place(
+        &mut self,
+        id: u64,
+        gravity: Gravity,
+        position: usize,
+        hole_position: usize,
+        after_hole: usize,
+    ) {
+        let slot = if position < hole_position
+            || (position == hole_position && gravity == Gravity::Left)
+        {
+            Slot {
+                gravity,
+                side: Side::BeforeHole,
+                offset: position,
+            }
+        } else {
+            Slot {
+                gravity,
+                side: Side::AfterHole,
+                offset: hole_position + after_hole - position,
+            }
+        };
+        self.set_mut(slot.side).insert((slot.offset, id));
+        self.slots.insert(id, slot);
+    }
+
+    // Removes the marks on `side` with offsets in `start..=end`
+    fn take(&mut self, side: Side, start: usize, end: usize) -> Vec<(u64, Slot)> {
+        let set = self.set_mut(side);
+        let keys = set
+            .range((start, 0)..=(end, u64::MAX))
+            .copied()
+            .collect::<Vec<_>>();
+        keys.into_iter()
+            .map(|key| {
+                self.set_mut(side).remove(&key);
+                (key.1, self.slots[&key.1])
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,616 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem;
+use core::ops::{Index, IndexMut, Range};
+use core::ptr;
+use core::slice;
+
+use allocator_api2::alloc::Global;
+
+use crate::error::check_bounds;
+use crate::raw::{self, RawBuf};
+use crate::HoleVecError;
+
+// A gap buffer with several holes, such as one per cursor of a multi-cursor
+// editor. The holes split `buf` into segments of values and are kept in the
+// order of their positions; holes at the same position are adjacent in `buf`.
+pub struct MultiHoleVec<T> {
+    buf: RawBuf<T>,
+    holes: Vec<Gap>,
+    len: usize,
+}
+
+// The unused slots `start..start + len` of the buffer
+#[derive(Clone, Copy, Debug)]
+struct Gap {
+    start: usize,
+    len: usize,
+}
+
+impl Gap {
+    fn end(self) -> usize {
+        self.start + self.len
+    }
+}
+
+// The slots of the values between hole `index - 1` and hole `index`
+fn segment_range(holes: &[Gap], capacity: usize, index: usize) -> Range<usize> {
+    let start = index.checked_sub(1).map_or(0, |index| holes[index].end());
+    let end = holes.get(index).map_or(capacity, |gap| gap.start);
+    start..end
+}
+
+impl<T> Default for MultiHoleVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MultiHoleVec<T> {
+    // Creates an empty `MultiHoleVec` with a single hole
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        let buf = RawBuf::with_capacity_in(capacity, Global);
+        let holes = alloc::vec![Gap {
+            start: 0,
+            len: buf.capacity(),
+        }];
+        Self { buf, holes, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    pub fn hole_count(&self) -> usize {
+        self.holes.len()
+    }
+
+    pub fn hole_position(&self, hole: usize) -> usize {
+        let gaps = self.holes[..hole].iter().map(|gap| gap.len).sum::<usize>();
+        self.holes[hole].start - gaps
+    }
+
+    // The position of every hole, in order
+    pub fn hole_positions(&self) -> impl ExactSizeIterator<Item = usize> + '_ {
+        let mut gaps = 0;
+        self.holes.iter().map(move |gap| {
+            let position = gap.start - gaps;
+            gaps += gap.len;
+            position
+        })
+    }
+
+    // Adds a hole at `position` and returns its index. It is placed after the
+    // holes that are already at `position`.
+    pub fn add_hole(&mut self, position: usize) -> usize {
+        assert!(position <= self.len);
+        let hole = self.hole_positions().take_while(|&p| p <= position).count();
+        let gaps = self.holes[..hole].iter().map(|gap| gap.len).sum::<usize>();
+        let gap = Gap {
+            start: position + gaps,
+            len: 0,
+        };
+        self.holes.insert(hole, gap);
+        hole
+    }
+
+    // Removes a hole, handing its unused capacity to a neighbouring hole. The
+    // last hole cannot be removed.
+    pub fn remove_hole(&mut self, hole: usize) {
+        assert!(hole < self.holes.len() && self.holes.len() > 1);
+        let gap = self.holes.remove(hole);
+        let ptr = self.buf.ptr();
+        if hole == 0 {
+            // Move the values between the hole and the next one to the left
+            let next = &mut self.holes[0];
+            let amount = next.start - gap.end();
+            unsafe { ptr::copy(ptr.add(gap.end()), ptr.add(gap.start), amount) };
+            next.start -= gap.len;
+            next.len += gap.len;
+        } else {
+            // Move the values between the previous hole and this one to the right
+            let previous = &mut self.holes[hole - 1];
+            let amount = gap.start - previous.end();
+            unsafe {
+                ptr::copy(
+                    ptr.add(previous.end()),
+                    ptr.add(previous.end() + gap.len),
+                    amount,
+                )
+            };
+            previous.len += gap.len;
+        }
+    }
+
+    // Makes room for at least `additional` more values at every hole
+    pub fn reserve(&mut self, additional: usize) {
+        if self.holes.iter().all(|gap| gap.len >= additional) {
+            return;
+        }
+        let needed = additional
+            .checked_mul(self.holes.len())
+            .and_then(|needed| needed.checked_add(self.len))
+            .unwrap_or_else(|| raw::handle_error(HoleVecError::CapacityOverflow));
+        // Spreading the unused capacity over the holes again only pays off if
+        // there is plenty of it
+        let capacity = if needed <= self.capacity() / 2 {
+            self.capacity()
+        } else {
+            needed.max(self.capacity().saturating_mul(2))
+        };
+        self.relayout(capacity);
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.relayout(self.len);
+    }
+
+    // Changes the capacity to `capacity` and spreads the unused capacity evenly
+    // over the holes
+    #[cold]
+    fn relayout(&mut self, capacity: usize) {
+        let old_capacity = self.capacity();
+        if capacity > old_capacity {
+            self.buf
+                .try_resize(capacity)
+                .unwrap_or_else(|err| raw::handle_error(err));
+        }
+        let capacity = if mem::size_of::<T>() == 0 {
+            old_capacity
+        } else {
+            capacity
+        };
+
+        let segments = (0..=self.holes.len())
+            .map(|index| segment_range(&self.holes, old_capacity, index))
+            .collect::<Vec<_>>();
+        let free = capacity - self.len;
+        let count = self.holes.len();
+        let mut starts = Vec::with_capacity(segments.len());
+        let mut start = 0;
+        for (index, segment) in segments.iter().enumerate() {
+            starts.push(start);
+            start += segment.len();
+            if let Some(gap) = self.holes.get_mut(index) {
+                let len = free / count + usize::from(index < free % count);
+                *gap = Gap { start, len };
+                start += len;
+            }
+        }
+
+        // Segments moving left are moved first, from left to right, so that
+        // no segment is overwritten before it has been moved
+        let ptr = self.buf.ptr();
+        let moves = segments.iter().zip(&starts);
+        for (segment, &start) in moves.clone().filter(|(s, &start)| start < s.start) {
+            unsafe { ptr::copy(ptr.add(segment.start), ptr.add(start), segment.len()) };
+        }
+        for (segment, &start) in moves.rev().filter(|(s, &start)| start > s.start) {
+            unsafe { ptr::copy(ptr.add(segment.start), ptr.add(start), segment.len()) };
+        }
+
+        if capacity < old_capacity {
+            self.buf
+                .try_resize(capacity)
+                .unwrap_or_else(|err| raw::handle_error(err));
+        }
+    }
+
+    // The values between hole `index - 1` and hole `index`, where segment 0
+    // starts at the beginning and the last segment ends at the end
+    pub fn segment(&self, index: usize) -> &[T] {
+        assert!(index <= self.holes.len());
+        let range = segment_range(&self.holes, self.capacity(), index);
+        unsafe { slice::from_raw_parts(self.buf.ptr().add(range.start), range.len()) }
+    }
+
+    pub fn segment_mut(&mut self, index: usize) -> &mut [T] {
+        assert!(index <= self.holes.len());
+        let range = segment_range(&self.holes, self.capacity(), index);
+        unsafe { slice::from_raw_parts_mut(self.buf.ptr().add(range.start), range.len()) }
+    }
+
+    pub fn push_before_hole(&mut self, hole: usize, value: T) {
+        if self.holes[hole].len == 0 {
+            self.reserve(1);
+        }
+        let gap = &mut self.holes[hole];
+        unsafe { self.buf.ptr().add(gap.start).write(value) };
+        gap.start += 1;
+        gap.len -= 1;
+        self.len += 1;
+    }
+
+    pub fn push_after_hole(&mut self, hole: usize, value: T) {
+        if self.holes[hole].len == 0 {
+            self.reserve(1);
+        }
+        let gap = &mut self.holes[hole];
+        gap.len -= 1;
+        unsafe { self.buf.ptr().add(gap.end()).write(value) };
+        self.len += 1;
+    }
+
+    pub fn pop_before_hole(&mut self, hole: usize) -> Option<T> {
+        if self.segment(hole).is_empty() {
+            return None;
+        }
+        let gap = &mut self.holes[hole];
+        gap.start -= 1;
+        gap.len += 1;
+        self.len -= 1;
+        Some(unsafe { self.buf.ptr().add(gap.start).read() })
+    }
+
+    pub fn pop_after_hole(&mut self, hole: usize) -> Option<T> {
+        if self.segment(hole + 1).is_empty() {
+            return None;
+        }
+        let gap = &mut self.holes[hole];
+        let value = unsafe { self.buf.ptr().add(gap.end()).read() };
+        gap.len += 1;
+        self.len -= 1;
+        Some(value)
+    }
+
+    // Inserts a copy of `value` before every hole
+    pub fn push_before_holes(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.reserve(1);
+        let last = self.holes.len() - 1;
+        for hole in 0..last {
+            self.push_before_hole(hole, value.clone());
+        }
+        self.push_before_hole(last, value);
+    }
+
+    // Inserts a copy of `value` after every hole
+    pub fn push_after_holes(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.reserve(1);
+        let last = self.holes.len() - 1;
+        for hole in 0..last {
+            self.push_after_hole(hole, value.clone());
+        }
+        self.push_after_hole(last, value);
+    }
+
+    // Removes the value before every hole, in the order of the holes
+    pub fn pop_before_holes(&mut self) -> Vec<Option<T>> {
+        (0..self.holes.len())
+            .map(|hole| self.pop_before_hole(hole))
+            .collect()
+    }
+
+    // Removes the value after every hole, in the order of the holes
+    pub fn pop_after_holes(&mut self) -> Vec<Option<T>> {
+        (0..self.holes.len())
+            .map(|hole| self.pop_after_hole(hole))
+            .collect()
+    }
+
+    // Moves a hole to the right, which cannot take it past the next hole
+    pub fn move_hole_right(&mut self, hole: usize, amount: usize) {
+        assert!(amount <= self.segment(hole + 1).len());
+        let gap = &mut self.holes[hole];
+        let ptr = self.buf.ptr();
+        unsafe { ptr::copy(ptr.add(gap.end()), ptr.add(gap.start), amount) };
+        gap.start += amount;
+    }
+
+    // Moves a hole to the left, which cannot take it past the previous hole
+    pub fn move_hole_left(&mut self, hole: usize, amount: usize) {
+        assert!(amount <= self.segment(hole).len());
+        let gap = &mut self.holes[hole];
+        gap.start -= amount;
+        let ptr = self.buf.ptr();
+        unsafe { ptr::copy(ptr.add(gap.start), ptr.add(gap.end()), amount) };
+    }
+
+    pub fn set_hole_position(&mut self, hole: usize, position: usize) {
+        let current = self.hole_position(hole);
+        if position > current {
+            self.move_hole_right(hole, position - current);
+        } else {
+            self.move_hole_left(hole, current - position);
+        }
+    }
+
+    pub fn try_move_hole_right(&mut self, hole: usize, amount: usize) -> Result<(), HoleVecError> {
+        check_bounds(amount, self.segment(hole + 1).len())?;
+        self.move_hole_right(hole, amount);
+        Ok(())
+    }
+
+    pub fn try_move_hole_left(&mut self, hole: usize, amount: usize) -> Result<(), HoleVecError> {
+        check_bounds(amount, self.segment(hole).len())?;
+        self.move_hole_left(hole, amount);
+        Ok(())
+    }
+
+    fn physical_index(&self, index: usize) -> usize {
+        let mut physical = index;
+        for gap in &self.holes {
+            if physical < gap.start {
+                break;
+            }
+            physical += gap.len;
+        }
+        physical
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index < self.len {
+            Some(unsafe { &*self.buf.ptr().add(self.physical_index(index)) })
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len {
+            Some(unsafe { &mut *self.buf.ptr().add(self.physical_index(index)) })
+        } else {
+            None
+        }
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        (0..=self.holes.len()).flat_map(move |index| self.segment(index))
+    }
+
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut T> + '_ {
+        let (ptr, capacity, holes) = (self.buf.ptr(), self.capacity(), &self.holes);
+        (0..=holes.len()).flat_map(move |index| {
+            let range = segment_range(holes, capacity, index);
+            unsafe { slice::from_raw_parts_mut(ptr.add(range.start), range.len()) }
+        })
+    }
+}
+
+impl<T> Drop for MultiHoleVec<T> {
+    fn drop(&mut self) {
+        for index in 0..=self.holes.len() {
+            unsafe { ptr::drop_in_place(self.segment_mut(index)) };
+        }
+    }
+}
+
+impl<T: Clone> Clone for MultiHoleVec<T> {
+    fn clone(&self) -> Self {
+        let mut clone = Self::with_capacity(self.len);
+        for value in self.iter() {
+            clone.push_before_hole(0, value.clone());
+        }
+        let mut positions = self.hole_positions();
+        clone.set_hole_position(0, positions.next().expect("BUG"));
+        for position in positions {
+            clone.add_hole(position);
+        }
+        clone
+    }
+}
+
+impl<T> Index<usize> for MultiHoleVec<T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for MultiHoleVec<T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for MultiHoleVec<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<MultiHoleVec<U>> for MultiHoleVec<T> {
+    fn eq(&self, other: &MultiHoleVec<U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: Eq> Eq for MultiHoleVec<T> {}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for MultiHoleVec<T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for MultiHoleVec<T> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialOrd> PartialOrd for MultiHoleVec<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord> Ord for MultiHoleVec<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash> Hash for MultiHoleVec<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+// Collects the values before a single hole
+impl<T> FromIterator<T> for MultiHoleVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut multi = Self::with_capacity(iter.size_hint().0);
+        iter.for_each(|value| multi.push_before_hole(0, value));
+        multi
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use rand::{thread_rng, Rng};
+    use std::prelude::v1::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn it_works() {
+        let mut rng = thread_rng();
+        let value = Rc::new(());
+        for _ in 0..1000 {
+            let mut multi = MultiHoleVec::<(u8, Rc<()>)>::new();
+            let mut values = Vec::new();
+            let mut positions = vec![0];
+
+            for _ in 0..100 {
+                assert_eq!(Rc::strong_count(&value), 1 + 2 * values.len());
+                let new_value = (rng.gen::<u8>(), value.clone());
+                let hole = rng.gen::<usize>() % positions.len();
+                let position = positions[hole];
+                let previous = hole.checked_sub(1).map_or(0, |hole| positions[hole]);
+                let next = positions.get(hole + 1).copied().unwrap_or(values.len());
+                match rng.gen::<u64>() % 12 {
+                    0 | 1 => {
+                        multi.push_before_hole(hole, new_value.clone());
+                        values.insert(position, new_value);
+                        positions[hole..].iter_mut().for_each(|p| *p += 1);
+                    }
+                    2 => {
+                        multi.push_after_hole(hole, new_value.clone());
+                        values.insert(position, new_value);
+                        positions[hole + 1..].iter_mut().for_each(|p| *p += 1);
+                    }
+                    3 => {
+                        let expected = (position > previous).then(|| values.remove(position - 1));
+                        if expected.is_some() {
+                            positions[hole..].iter_mut().for_each(|p| *p -= 1);
+                        }
+                        assert_eq!(multi.pop_before_hole(hole), expected);
+                    }
+                    4 => {
+                        let expected = (position < next).then(|| values.remove(position));
+                        if expected.is_some() {
+                            positions[hole + 1..].iter_mut().for_each(|p| *p -= 1);
+                        }
+                        assert_eq!(multi.pop_after_hole(hole), expected);
+                    }
+                    5 => {
+                        multi.push_before_holes(new_value.clone());
+                        for hole in 0..positions.len() {
+                            values.insert(positions[hole], new_value.clone());
+                            positions[hole..].iter_mut().for_each(|p| *p += 1);
+                        }
+                    }
+                    6 => {
+                        let popped = multi.pop_before_holes();
+                        assert_eq!(popped.len(), positions.len());
+                        for (hole, popped) in popped.into_iter().enumerate() {
+                            let previous = hole.checked_sub(1).map_or(0, |hole| positions[hole]);
+                            let position = positions[hole];
+                            let expected =
+                                (position > previous).then(|| values.remove(position - 1));
+                            if expected.is_some() {
+                                positions[hole..].iter_mut().for_each(|p| *p -= 1);
+                            }
+                            assert_eq!(popped, expected);
+                        }
+                    }
+                    7 | 8 => {
+                        let position = rng.gen::<usize>() % (values.len() + 1);
+                        let expected = positions.iter().take_while(|&&p| p <= position).count();
+                        assert_eq!(multi.add_hole(position), expected);
+                        positions.insert(expected, position);
+                    }
+                    9 => {
+                        if positions.len() > 1 {
+                            multi.remove_hole(hole);
+                            positions.remove(hole);
+                        }
+                    }
+                    10 => {
+                        let target = previous + rng.gen::<usize>() % (next - previous + 1);
+                        multi.set_hole_position(hole, target);
+                        positions[hole] = target;
+                        let amount = rng.gen::<usize>() % (next - previous + 2);
+                        let result = multi.try_move_hole_right(hole, amount);
+                        assert_eq!(result, bounds_result(amount, next - target));
+                        if result.is_ok() {
+                            positions[hole] += amount;
+                        }
+                    }
+                    _ => {
+                        multi.shrink_to_fit();
+                        assert_eq!(multi.capacity(), values.len());
+                    }
+                }
+
+                assert!(multi.hole_positions().eq(positions.iter().copied()));
+                for (hole, &position) in positions.iter().enumerate() {
+                    assert_eq!(multi.hole_position(hole), position);
+                }
+                let mut start = 0;
+                for (index, &end) in positions.iter().chain([&values.len()]).enumerate() {
+                    assert_eq!(multi.segment(index), &values[start..end]);
+                    start = end;
+                }
+                assert_eq!(multi, values);
+                assert_values!(multi, values);
+                assert!(multi.capacity() >= values.len());
+
+                let clone = multi.clone();
+                assert!(clone.hole_positions().eq(positions.iter().copied()));
+                assert_values!(clone, values);
+            }
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn zero_sized_values() {
+        let mut multi = MultiHoleVec::<()>::new();
+        multi.add_hole(0);
+        for _ in 0..10 {
+            multi.push_before_holes(());
+        }
+        multi.push_after_hole(0, ());
+        assert_eq!(multi.len(), 21);
+        assert!(multi.hole_positions().eq([10, 21]));
+        assert_eq!(multi.pop_after_holes(), [Some(()), None]);
+        multi.shrink_to_fit();
+        assert_eq!(multi.iter().count(), 20);
+    }
+}
@@ -0,0 +1,116 @@
+use core::alloc::Layout;
+use core::mem;
+use core::ptr::NonNull;
+
+use allocator_api2::alloc::{Allocator, Global};
+
+use crate::HoleVecError;
+
+// An owned allocation with room for `cap` values. It never reads or drops the
+// values it holds; keeping track of which slots are initialized is up to the
+// owner.
+pub(crate) struct RawBuf<T, A: Allocator = Global> {
+    ptr: NonNull<T>,
+    cap: usize,
+    alloc: A,
+}
+
+unsafe impl<T: Send, A: Allocator + Send> Send for RawBuf<T, A> {}
+unsafe impl<T: Sync, A: Allocator + Sync> Sync for RawBuf<T, A> {}
+
+impl<T> RawBuf<T> {
+    // Safety: `ptr` must have been allocated by the global allocator with the
+    // layout of `[T; cap]`, as is the case for the buffer of a `Vec<T>`.
+    pub(crate) unsafe fn from_raw_parts(ptr: *mut T, cap: usize) -> Self {
+        Self {
+            ptr: NonNull::new_unchecked(ptr),
+            cap: if Self::IS_ZST { usize::MAX } else { cap },
+            alloc: Global,
+        }
+    }
+}
+
+impl<T, A: Allocator> RawBuf<T, A> {
+    const IS_ZST: bool = mem::size_of::<T>() == 0;
+
+    pub(crate) fn new_in(alloc: A) -> Self {
+        Self {
+            ptr: NonNull::dangling(),
+            cap: if Self::IS_ZST { usize::MAX } else { 0 },
+            alloc,
+        }
+    }
+
+    pub(crate) fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::try_with_capacity_in(capacity, alloc).unwrap_or_else(|err| handle_error(err))
+    }
+
+    pub(crate) fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, HoleVecError> {
+        let mut buf = Self::new_in(alloc);
+        if capacity > buf.cap {
+            buf.try_resize(capacity)?;
+        }
+        Ok(buf)
+    }
+
+    pub(crate) fn ptr(&self) -> *mut T {
+        self.ptr.as_ptr()
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.cap
+    }
+
+    pub(crate) fn allocator(&self) -> &A {
+        &self.alloc
+    }
+
+    // Changes the capacity to `capacity`, keeping the first
+    // `min(self.cap, capacity)` slots.
+    pub(crate) fn try_resize(&mut self, capacity: usize) -> Result<(), HoleVecError> {
+        if Self::IS_ZST || capacity == self.cap {
+            return Ok(());
+        }
+
+        let new_layout =
+            Layout::array::<T>(capacity).map_err(|_| HoleVecError::CapacityOverflow)?;
+        let result = if capacity == 0 {
+            unsafe { self.alloc.deallocate(self.ptr.cast(), self.layout()) };
+            Ok(NonNull::dangling())
+        } else if self.cap == 0 {
+            self.alloc.allocate(new_layout).map(NonNull::cast)
+        } else if capacity > self.cap {
+            unsafe { self.alloc.grow(self.ptr.cast(), self.layout(), new_layout) }
+                .map(NonNull::cast)
+        } else {
+            unsafe {
+                self.alloc
+                    .shrink(self.ptr.cast(), self.layout(), new_layout)
+            }
+            .map(NonNull::cast)
+        };
+
+        self.ptr = result.map_err(|_| HoleVecError::AllocError { layout: new_layout })?;
+        self.cap = capacity;
+        Ok(())
+    }
+
+    fn layout(&self) -> Layout {
+        Layout::array::<T>(self.cap).expect("BUG")
+    }
+}
+
+impl<T, A: Allocator> Drop for RawBuf<T, A> {
+    fn drop(&mut self) {
+        if !Self::IS_ZST && self.cap != 0 {
+            unsafe { self.alloc.deallocate(self.ptr.cast(), self.layout()) };
+        }
+    }
+}
+
+pub(crate) fn handle_error(err: HoleVecError) -> ! {
+    match err {
+        HoleVecError::AllocError { layout } => alloc::alloc::handle_alloc_error(layout),
+        err => panic!("{}", err),
+    }
+}
@@ -0,0 +1,156 @@
+use core::fmt;
+use core::iter::FusedIterator;
+
+// Finds the first or last occurrence of a needle in a single slice
+pub(crate) type SliceSearch<T> = fn(&[T], &[T]) -> Option<usize>;
+
+pub(crate) fn find_in_slice<T: PartialEq>(haystack: &[T], needle: &[T]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+pub(crate) fn rfind_in_slice<T: PartialEq>(haystack: &[T], needle: &[T]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(haystack.len());
+    }
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+}
+
+#[cfg(feature = "memchr")]
+pub(crate) fn find_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::find(haystack, needle)
+}
+
+#[cfg(feature = "memchr")]
+pub(crate) fn rfind_bytes(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    memchr::memmem::rfind(haystack, needle)
+}
+
+// Returns the first match of `needle` that starts at or after `start`. The
+// slices are searched separately, so only matches straddling the hole are
+// compared value by value.
+pub(crate) fn find_from<T: PartialEq>(
+    (before_hole, after_hole): (&[T], &[T]),
+    needle: &[T],
+    start: usize,
+    search: SliceSearch<T>,
+) -> Option<usize> {
+    let hole_position = before_hole.len();
+    if start + needle.len() > hole_position + after_hole.len() {
+        return None;
+    }
+
+    if start < hole_position {
+        if let Some(index) = search(&before_hole[start..], needle) {
+            return Some(start + index);
+        }
+    }
+
+    let len = hole_position + after_hole.len();
+    let straddling = start.max((hole_position + 1).saturating_sub(needle.len()))..hole_position;
+    for position in straddling.filter(|&position| position + needle.len() <= len) {
+        if matches_at((before_hole, after_hole), needle, position) {
+            return Some(position);
+        }
+    }
+
+    let start = start.max(hole_position);
+    let index = search(&after_hole[start - hole_position..], needle)?;
+    Some(start + index)
+}
+
+// Returns the last match of `needle` that ends at or before `end`
+pub(crate) fn rfind_to<T: PartialEq>(
+    (before_hole, after_hole): (&[T], &[T]),
+    needle: &[T],
+    end: usize,
+    search: SliceSearch<T>,
+) -> Option<usize> {
+    let hole_position = before_hole.len();
+    let last_start = end.checked_sub(needle.len())?;
+
+    if end > hole_position {
+        if let Some(index) = search(&after_hole[..end - hole_position], needle) {
+            return Some(hole_position + index);
+        }
+    }
+
+    let straddling = (hole_position + 1).saturating_sub(needle.len())..hole_position;
+    for position in straddling.rev().filter(|&position| position <= last_start) {
+        if matches_at((before_hole, after_hole), needle, position) {
+            return Some(position);
+        }
+    }
+
+    search(&before_hole[..end.min(hole_position)], needle)
+}
+
+fn matches_at<T: PartialEq>(
+    (before_hole, after_hole): (&[T], &[T]),
+    needle: &[T],
+    position: usize,
+) -> bool {
+    let (head, tail) = needle.split_at(before_hole.len() - position);
+    before_hole[position..] == *head && after_hole[..tail.len()] == *tail
+}
+
+// An iterator over the positions of the non-overlapping matches of a needle
+// in a `HoleVec`, from front to back.
+pub struct FindIter<'a, 'b, T> {
+    slices: (&'a [T], &'a [T]),
+    needle: &'b [T],
+    // Where to continue searching, or `None` once the end has been reached
+    start: Option<usize>,
+    search: SliceSearch<T>,
+}
+
+impl<'a, 'b, T> FindIter<'a, 'b, T> {
+    pub(crate) fn new(slices: (&'a [T], &'a [T]), needle: &'b [T], search: SliceSearch<T>) -> Self {
+        Self {
+            slices,
+            needle,
+            start: Some(0),
+            search,
+        }
+    }
+}
+
+impl<T: PartialEq> Iterator for FindIter<'_, '_, T> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        let start = self.start?;
+        let position = find_from(self.slices, self.needle, start, self.search);
+        // An empty needle matches at every position
+        self.start = position.map(|position| position + self.needle.len().max(1));
+        position
+    }
+}
+
+impl<T: PartialEq> FusedIterator for FindIter<'_, '_, T> {}
+
+impl<T> Clone for FindIter<'_, '_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            slices: self.slices,
+            needle: self.needle,
+            start: self.start,
+            search: self.search,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for FindIter<'_, '_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FindIter")
+            .field("needle", &self.needle)
+            .field("start", &self.start)
+            .finish()
+    }
+}
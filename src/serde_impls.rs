@@ -0,0 +1,129 @@
+use alloc::vec::Vec;
+use core::fmt;
+use core::marker::PhantomData;
+
+use allocator_api2::alloc::Allocator;
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::HoleVec;
+
+// A `HoleVec` is serialized as a struct holding its values in order and the
+// position of the hole, so that formats without field names still work.
+const FIELDS: &[&str] = &["values", "hole_position"];
+
+struct Values<'a, T, A: Allocator>(&'a HoleVec<T, A>);
+
+impl<T: Serialize, A: Allocator> Serialize for Values<'_, T, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter())
+    }
+}
+
+impl<T: Serialize, A: Allocator> Serialize for HoleVec<T, A> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("HoleVec", FIELDS.len())?;
+        state.serialize_field("values", &Values(self))?;
+        state.serialize_field("hole_position", &self.len_before_hole())?;
+        state.end()
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for HoleVec<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("HoleVec", FIELDS, HoleVecVisitor(PhantomData))
+    }
+}
+
+enum Field {
+    Values,
+    HolePosition,
+}
+
+impl<'de> Deserialize<'de> for Field {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct FieldVisitor;
+
+impl Visitor<'_> for FieldVisitor {
+    type Value = Field;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("`values` or `hole_position`")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Field, E> {
+        match value {
+            0 => Ok(Field::Values),
+            1 => Ok(Field::HolePosition),
+            _ => Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(value),
+                &"a field index below 2",
+            )),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Field, E> {
+        match value {
+            "values" => Ok(Field::Values),
+            "hole_position" => Ok(Field::HolePosition),
+            _ => Err(de::Error::unknown_field(value, FIELDS)),
+        }
+    }
+}
+
+struct HoleVecVisitor<T>(PhantomData<T>);
+
+impl<T> HoleVecVisitor<T> {
+    fn build<E: de::Error>(values: Vec<T>, hole_position: usize) -> Result<HoleVec<T>, E> {
+        if hole_position > values.len() {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(hole_position as u64),
+                &"a hole position within the values",
+            ));
+        }
+        Ok(HoleVec::from_vec(values, hole_position))
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for HoleVecVisitor<T> {
+    type Value = HoleVec<T>;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("struct HoleVec")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<HoleVec<T>, A::Error> {
+        let values = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let hole_position = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        Self::build(values, hole_position)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<HoleVec<T>, A::Error> {
+        let mut values = None;
+        let mut hole_position = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                Field::Values if values.is_some() => {
+                    return Err(de::Error::duplicate_field("values"))
+                }
+                Field::Values => values = Some(map.next_value()?),
+                Field::HolePosition if hole_position.is_some() => {
+                    return Err(de::Error::duplicate_field("hole_position"))
+                }
+                Field::HolePosition => hole_position = Some(map.next_value()?),
+            }
+        }
+        let values = values.ok_or_else(|| de::Error::missing_field("values"))?;
+        let hole_position =
+            hole_position.ok_or_else(|| de::Error::missing_field("hole_position"))?;
+        Self::build(values, hole_position)
+    }
+}
@@ -0,0 +1,259 @@
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops::{Index, IndexMut, Range, RangeBounds};
+
+use crate::error::range_bounds;
+use crate::iter::{Iter, IterMut};
+
+// A view of a range of values in a gap buffer, made of the part of the range
+// before the hole and the part after it.
+pub struct HoleSlice<'a, T> {
+    slices: (&'a [T], &'a [T]),
+}
+
+pub struct HoleSliceMut<'a, T> {
+    slices: (&'a mut [T], &'a mut [T]),
+}
+
+// The index ranges of `start..end` within the two slices, where the first
+// slice holds `split` values
+fn split(split: usize, start: usize, end: usize) -> (Range<usize>, Range<usize>) {
+    (
+        start.min(split)..end.min(split),
+        start.saturating_sub(split)..end.saturating_sub(split),
+    )
+}
+
+impl<'a, T> HoleSlice<'a, T> {
+    pub(crate) fn new<R: RangeBounds<usize>>(slices: (&'a [T], &'a [T]), range: R) -> Self {
+        let (start, end) = range_bounds(range, slices.0.len() + slices.1.len());
+        let (first, second) = split(slices.0.len(), start, end);
+        Self {
+            slices: (&slices.0[first], &slices.1[second]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slices.0.len() + self.slices.1.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        match index.checked_sub(self.slices.0.len()) {
+            None => self.slices.0.get(index),
+            Some(index) => self.slices.1.get(index),
+        }
+    }
+
+    pub fn first(&self) -> Option<&'a T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&'a T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    pub fn as_slices(&self) -> (&'a [T], &'a [T]) {
+        self.slices
+    }
+
+    pub fn iter(&self) -> Iter<'a, T> {
+        Iter::new(self.slices)
+    }
+
+    // A view of a range of this view
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> HoleSlice<'a, T> {
+        HoleSlice::new(self.slices, range)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut vec = Vec::with_capacity(self.len());
+        vec.extend_from_slice(self.slices.0);
+        vec.extend_from_slice(self.slices.1);
+        vec
+    }
+}
+
+impl<'a, T> HoleSliceMut<'a, T> {
+    pub(crate) fn new<R: RangeBounds<usize>>(slices: (&'a mut [T], &'a mut [T]), range: R) -> Self {
+        let (start, end) = range_bounds(range, slices.0.len() + slices.1.len());
+        let (first, second) = split(slices.0.len(), start, end);
+        Self {
+            slices: (&mut slices.0[first], &mut slices.1[second]),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.slices.0.len() + self.slices.1.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.as_hole_slice().get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        match index.checked_sub(self.slices.0.len()) {
+            None => self.slices.0.get_mut(index),
+            Some(index) => self.slices.1.get_mut(index),
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (self.slices.0, self.slices.1)
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        (self.slices.0, self.slices.1)
+    }
+
+    pub fn as_hole_slice(&self) -> HoleSlice<'_, T> {
+        HoleSlice {
+            slices: self.as_slices(),
+        }
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slices())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.as_mut_slices())
+    }
+
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> HoleSlice<'_, T> {
+        HoleSlice::new(self.as_slices(), range)
+    }
+
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> HoleSliceMut<'_, T> {
+        HoleSliceMut::new(self.as_mut_slices(), range)
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        self.as_hole_slice().to_vec()
+    }
+}
+
+impl<T> Clone for HoleSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for HoleSlice<'_, T> {}
+
+impl<T> Index<usize> for HoleSlice<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> Index<usize> for HoleSliceMut<'_, T> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("index out of bounds")
+    }
+}
+
+impl<T> IndexMut<usize> for HoleSliceMut<'_, T> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("index out of bounds")
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HoleSlice<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for HoleSliceMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<HoleSlice<'_, U>> for HoleSlice<'_, T> {
+    fn eq(&self, other: &HoleSlice<'_, U>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: Eq> Eq for HoleSlice<'_, T> {}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for HoleSlice<'_, T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<&[U]> for HoleSlice<'_, T> {
+    fn eq(&self, other: &&[U]) -> bool {
+        *self == **other
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for HoleSlice<'_, T> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<[U]> for HoleSliceMut<'_, T> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.as_hole_slice() == *other
+    }
+}
+
+impl<'a, T> IntoIterator for HoleSlice<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &HoleSlice<'a, T> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T> IntoIterator for HoleSliceMut<'a, T> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        IterMut::new(self.slices)
+    }
+}
@@ -0,0 +1,738 @@
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::{FromIterator, FusedIterator};
+use core::ops::{Index, IndexMut, RangeBounds};
+
+use crate::error::range_bounds;
+use crate::iter::{Drain, Iter, IterMut};
+use crate::raw;
+use crate::search::FindIter;
+use crate::slice::{HoleSlice, HoleSliceMut};
+use crate::{ArrayIntoIter, HoleArray, HoleVec, HoleVecError, IntoIter};
+
+macro_rules! dispatch {
+    ($ty:ident: $storage:expr, $inner:ident => $body:expr) => {
+        match $storage {
+            $ty::Inline($inner) => $body,
+            $ty::Heap($inner) => $body,
+        }
+    };
+    ($storage:expr, $inner:ident => $body:expr) => {
+        dispatch!(Storage: $storage, $inner => $body)
+    };
+}
+
+// A `HoleVec` that keeps up to `N` values inline and only moves them to the
+// heap once they stop fitting, which saves short-lived buffers an allocation.
+pub struct SmallHoleVec<T, const N: usize> {
+    storage: Storage<T, N>,
+}
+
+enum Storage<T, const N: usize> {
+    Inline(HoleArray<T, N>),
+    Heap(HoleVec<T>),
+}
+
+impl<T, const N: usize> Default for SmallHoleVec<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize> SmallHoleVec<T, N> {
+    pub fn new() -> Self {
+        Self {
+            storage: Storage::Inline(HoleArray::new()),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        if capacity <= N {
+            Self::new()
+        } else {
+            Self {
+                storage: Storage::Heap(HoleVec::with_capacity(capacity)),
+            }
+        }
+    }
+
+    // Whether the values have been moved to the heap
+    pub fn spilled(&self) -> bool {
+        matches!(self.storage, Storage::Heap(_))
+    }
+
+    pub fn len(&self) -> usize {
+        dispatch!(&self.storage, inner => inner.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len_before_hole(&self) -> usize {
+        dispatch!(&self.storage, inner => inner.len_before_hole())
+    }
+
+    pub fn len_after_hole(&self) -> usize {
+        dispatch!(&self.storage, inner => inner.len_after_hole())
+    }
+
+    pub fn capacity(&self) -> usize {
+        dispatch!(&self.storage, inner => inner.capacity())
+    }
+
+    pub fn gap_len(&self) -> usize {
+        dispatch!(&self.storage, inner => inner.gap_len())
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if self.len().saturating_add(additional) > self.capacity() {
+            self.spill(additional);
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), HoleVecError> {
+        let capacity = self
+            .len()
+            .checked_add(additional)
+            .ok_or(HoleVecError::CapacityOverflow)?;
+        if capacity > self.capacity() {
+            self.try_spill(capacity.max(2 * N))?
+                .try_reserve(additional)?;
+        }
+        Ok(())
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.try_reserve_exact(additional)
+            .unwrap_or_else(|err| raw::handle_error(err))
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), HoleVecError> {
+        let capacity = self
+            .len()
+            .checked_add(additional)
+            .ok_or(HoleVecError::CapacityOverflow)?;
+        if capacity > self.capacity() {
+            self.try_spill(capacity)?.try_reserve_exact(additional)?;
+        }
+        Ok(())
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    // Moves the values back inline if `min_capacity` and the values fit, and
+    // otherwise shrinks the heap allocation
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        if let Storage::Heap(vec) = &mut self.storage {
+            if min_capacity.max(vec.len()) > N {
+                vec.shrink_to(min_capacity);
+                return;
+            }
+            let mut array = HoleArray::new();
+            for value in vec.drain_before_hole(vec.len_before_hole()) {
+                let _ = array.push_before_hole(value);
+            }
+            for value in vec.drain_after_hole(vec.len_after_hole()).rev() {
+                let _ = array.push_after_hole(value);
+            }
+            self.storage = Storage::Inline(array);
+        }
+    }
+
+    // Moves the values to the heap, with room for at least `additional` more
+    fn spill(&mut self, additional: usize) -> &mut HoleVec<T> {
+        let capacity = self.len().saturating_add(additional).max(2 * N);
+        let vec = self
+            .try_spill(capacity)
+            .unwrap_or_else(|err| raw::handle_error(err));
+        vec.reserve(additional);
+        vec
+    }
+
+    // Moves the values to a heap allocation of `capacity` unless they already
+    // are on the heap
+    fn try_spill(&mut self, capacity: usize) -> Result<&mut HoleVec<T>, HoleVecError> {
+        if let Storage::Inline(array) = &mut self.storage {
+            let mut vec = HoleVec::try_with_capacity(capacity)?;
+            vec.extend_before_hole(array.drain_before_hole(array.len_before_hole()));
+            vec.extend_after_hole(array.drain_after_hole(array.len_after_hole()));
+            self.storage = Storage::Heap(vec);
+        }
+        match &mut self.storage {
+            Storage::Heap(vec) => Ok(vec),
+            Storage::Inline(_) => unreachable!(),
+        }
+    }
+
+    pub fn push_before_hole(&mut self, value: T) {
+        let value = match &mut self.storage {
+            Storage::Inline(array) => match array.push_before_hole(value) {
+                Ok(()) => return,
+                Err(value) => value,
+            },
+            Storage::Heap(_) => value,
+        };
+        self.spill(1).push_before_hole(value);
+    }
+
+    pub fn push_after_hole(&mut self, value: T) {
+        let value = match &mut self.storage {
+            Storage::Inline(array) => match array.push_after_hole(value) {
+                Ok(()) => return,
+                Err(value) => value,
+            },
+            Storage::Heap(_) => value,
+        };
+        self.spill(1).push_after_hole(value);
+    }
+
+    pub fn pop_before_hole(&mut self) -> Option<T> {
+        dispatch!(&mut self.storage, inner => inner.pop_before_hole())
+    }
+
+    pub fn pop_after_hole(&mut self) -> Option<T> {
+        dispatch!(&mut self.storage, inner => inner.pop_after_hole())
+    }
+
+    pub fn extend_before_hole<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|value| self.push_before_hole(value));
+    }
+
+    pub fn extend_after_hole<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let len = self.len_before_hole();
+        self.extend_before_hole(iter);
+        self.move_hole_left(self.len_before_hole() - len);
+    }
+
+    pub fn extend_from_slice_before_hole(&mut self, values: &[T])
+    where
+        T: Clone,
+    {
+        self.extend_before_hole(values.iter().cloned());
+    }
+
+    pub fn extend_from_slice_after_hole(&mut self, values: &[T])
+    where
+        T: Clone,
+    {
+        self.extend_after_hole(values.iter().cloned());
+    }
+
+    pub fn drain_before_hole(&mut self, amount: usize) -> Drain<'_, T> {
+        dispatch!(&mut self.storage, inner => inner.drain_before_hole(amount))
+    }
+
+    pub fn drain_after_hole(&mut self, amount: usize) -> Drain<'_, T> {
+        dispatch!(&mut self.storage, inner => inner.drain_after_hole(amount))
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.set_hole_position(index);
+        self.push_before_hole(value);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        dispatch!(&mut self.storage, inner => inner.remove(index))
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        dispatch!(&mut self.storage, inner => inner.drain(range))
+    }
+
+    // Like `HoleVec::splice`, the replacement values are inserted right away.
+    // They are moved to the heap if they do not fit inline.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = range_bounds(range, self.len());
+        self.set_hole_position(start);
+        self.extend_before_hole(replace_with);
+        self.drain_after_hole(end - start)
+    }
+
+    pub fn move_hole_right(&mut self, amount: usize) {
+        dispatch!(&mut self.storage, inner => inner.move_hole_right(amount))
+    }
+
+    pub fn move_hole_left(&mut self, amount: usize) {
+        dispatch!(&mut self.storage, inner => inner.move_hole_left(amount))
+    }
+
+    pub fn set_hole_position(&mut self, position: usize) {
+        dispatch!(&mut self.storage, inner => inner.set_hole_position(position))
+    }
+
+    pub fn try_move_hole_right(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        dispatch!(&mut self.storage, inner => inner.try_move_hole_right(amount))
+    }
+
+    pub fn try_move_hole_left(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        dispatch!(&mut self.storage, inner => inner.try_move_hole_left(amount))
+    }
+
+    pub fn try_set_hole_position(&mut self, position: usize) -> Result<(), HoleVecError> {
+        dispatch!(&mut self.storage, inner => inner.try_set_hole_position(position))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        dispatch!(&self.storage, inner => inner.get(index))
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        dispatch!(&mut self.storage, inner => inner.get_mut(index))
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.get(self.len().checked_sub(1)?)
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(self.len().checked_sub(1)?)
+    }
+
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        dispatch!(&self.storage, inner => inner.as_slices())
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        dispatch!(&mut self.storage, inner => inner.as_mut_slices())
+    }
+
+    pub fn as_slices_before_hole(&self) -> &[T] {
+        self.as_slices().0
+    }
+
+    pub fn as_slices_after_hole(&self) -> &[T] {
+        self.as_slices().1
+    }
+
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> HoleSlice<'_, T> {
+        HoleSlice::new(self.as_slices(), range)
+    }
+
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> HoleSliceMut<'_, T> {
+        HoleSliceMut::new(self.as_mut_slices(), range)
+    }
+
+    pub fn as_mut_slices_before_hole(&mut self) -> &mut [T] {
+        self.as_mut_slices().0
+    }
+
+    pub fn as_mut_slices_after_hole(&mut self) -> &mut [T] {
+        self.as_mut_slices().1
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slices())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.as_mut_slices())
+    }
+
+    pub fn iter_before_hole(&self) -> Iter<'_, T> {
+        Iter::new((self.as_slices_before_hole(), &[]))
+    }
+
+    pub fn iter_after_hole(&self) -> Iter<'_, T> {
+        Iter::new((&[], self.as_slices_after_hole()))
+    }
+
+    pub fn position<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<usize> {
+        self.iter().position(predicate)
+    }
+
+    pub fn into_hole_vec(self) -> HoleVec<T> {
+        match self.storage {
+            Storage::Inline(mut array) => {
+                let mut vec = HoleVec::with_capacity(array.len());
+                vec.extend_before_hole(array.drain_before_hole(array.len_before_hole()));
+                vec.extend_after_hole(array.drain_after_hole(array.len_after_hole()));
+                vec
+            }
+            Storage::Heap(vec) => vec,
+        }
+    }
+}
+
+impl<T: PartialEq, const N: usize> SmallHoleVec<T, N> {
+    pub fn contains(&self, value: &T) -> bool {
+        dispatch!(&self.storage, inner => inner.contains(value))
+    }
+
+    // Takes O(n * m) time, see `HoleVec::find`
+    pub fn find(&self, needle: &[T]) -> Option<usize> {
+        dispatch!(&self.storage, inner => inner.find(needle))
+    }
+
+    pub fn rfind(&self, needle: &[T]) -> Option<usize> {
+        dispatch!(&self.storage, inner => inner.rfind(needle))
+    }
+
+    pub fn find_iter<'a, 'b>(&'a self, needle: &'b [T]) -> FindIter<'a, 'b, T> {
+        dispatch!(&self.storage, inner => inner.find_iter(needle))
+    }
+}
+
+#[cfg(feature = "memchr")]
+impl<const N: usize> SmallHoleVec<u8, N> {
+    pub fn find_bytes(&self, needle: &[u8]) -> Option<usize> {
+        dispatch!(&self.storage, inner => inner.find_bytes(needle))
+    }
+
+    pub fn rfind_bytes(&self, needle: &[u8]) -> Option<usize> {
+        dispatch!(&self.storage, inner => inner.rfind_bytes(needle))
+    }
+
+    pub fn find_bytes_iter<'a, 'b>(&'a self, needle: &'b [u8]) -> FindIter<'a, 'b, u8> {
+        dispatch!(&self.storage, inner => inner.find_bytes_iter(needle))
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallHoleVec<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            storage: match &self.storage {
+                Storage::Inline(array) => Storage::Inline(array.clone()),
+                Storage::Heap(vec) => Storage::Heap(vec.clone()),
+            },
+        }
+    }
+}
+
+impl<T, const N: usize> Index<usize> for SmallHoleVec<T, N> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        dispatch!(&self.storage, inner => &inner[index])
+    }
+}
+
+impl<T, const N: usize> IndexMut<usize> for SmallHoleVec<T, N> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        dispatch!(&mut self.storage, inner => &mut inner[index])
+    }
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallHoleVec<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<SmallHoleVec<U, M>>
+    for SmallHoleVec<T, N>
+{
+    fn eq(&self, other: &SmallHoleVec<U, M>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: Eq, const N: usize> Eq for SmallHoleVec<T, N> {}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<Vec<U>> for SmallHoleVec<T, N> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U]> for SmallHoleVec<T, N> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<&[U]> for SmallHoleVec<T, N> {
+    fn eq(&self, other: &&[U]) -> bool {
+        *self == **other
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize, const M: usize> PartialEq<[U; M]> for SmallHoleVec<T, N> {
+    fn eq(&self, other: &[U; M]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialOrd, const N: usize> PartialOrd for SmallHoleVec<T, N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, const N: usize> Ord for SmallHoleVec<T, N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, const N: usize> Hash for SmallHoleVec<T, N> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_usize(self.len());
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+impl<T, const N: usize> Extend<T> for SmallHoleVec<T, N> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_before_hole(iter)
+    }
+}
+
+impl<'a, T: Copy + 'a, const N: usize> Extend<&'a T> for SmallHoleVec<T, N> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend_before_hole(iter.into_iter().copied())
+    }
+}
+
+impl<T, const N: usize> FromIterator<T> for SmallHoleVec<T, N> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut small = Self::new();
+        small.extend_before_hole(iter);
+        small
+    }
+}
+
+impl<T, const N: usize> From<HoleVec<T>> for SmallHoleVec<T, N> {
+    fn from(vec: HoleVec<T>) -> Self {
+        let mut small = Self {
+            storage: Storage::Heap(vec),
+        };
+        small.shrink_to_fit();
+        small
+    }
+}
+
+impl<T, const N: usize> From<SmallHoleVec<T, N>> for HoleVec<T> {
+    fn from(small: SmallHoleVec<T, N>) -> Self {
+        small.into_hole_vec()
+    }
+}
+
+// Reads the values out of a `SmallHoleVec` one by one, wherever they are stored
+pub struct SmallIntoIter<T, const N: usize> {
+    inner: IntoIterStorage<T, N>,
+}
+
+enum IntoIterStorage<T, const N: usize> {
+    Inline(ArrayIntoIter<T, N>),
+    Heap(IntoIter<T>),
+}
+
+impl<T: fmt::Debug, const N: usize> fmt::Debug for SmallIntoIter<T, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let slices = dispatch!(IntoIterStorage: &self.inner, inner => inner.as_slices());
+        f.debug_tuple("SmallIntoIter")
+            .field(&Iter::new(slices))
+            .finish()
+    }
+}
+
+impl<T: Clone, const N: usize> Clone for SmallIntoIter<T, N> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: match &self.inner {
+                IntoIterStorage::Inline(iter) => IntoIterStorage::Inline(iter.clone()),
+                IntoIterStorage::Heap(iter) => IntoIterStorage::Heap(iter.clone()),
+            },
+        }
+    }
+}
+
+impl<T, const N: usize> Iterator for SmallIntoIter<T, N> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        dispatch!(IntoIterStorage: &mut self.inner, inner => inner.next())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        dispatch!(IntoIterStorage: &self.inner, inner => inner.size_hint())
+    }
+}
+
+impl<T, const N: usize> DoubleEndedIterator for SmallIntoIter<T, N> {
+    fn next_back(&mut self) -> Option<T> {
+        dispatch!(IntoIterStorage: &mut self.inner, inner => inner.next_back())
+    }
+}
+
+impl<T, const N: usize> ExactSizeIterator for SmallIntoIter<T, N> {}
+
+impl<T, const N: usize> FusedIterator for SmallIntoIter<T, N> {}
+
+impl<T, const N: usize> IntoIterator for SmallHoleVec<T, N> {
+    type Item = T;
+    type IntoIter = SmallIntoIter<T, N>;
+
+    fn into_iter(self) -> SmallIntoIter<T, N> {
+        let inner = match self.storage {
+            Storage::Inline(array) => IntoIterStorage::Inline(array.into_iter()),
+            Storage::Heap(vec) => IntoIterStorage::Heap(vec.into_iter()),
+        };
+        SmallIntoIter { inner }
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a SmallHoleVec<T, N> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<'a, T, const N: usize> IntoIterator for &'a mut SmallHoleVec<T, N> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T>;
+
+    fn into_iter(self) -> IterMut<'a, T> {
+        self.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::*;
+    use rand::{thread_rng, Rng};
+    use std::prelude::v1::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn it_works() {
+        let mut rng = thread_rng();
+        let value = Rc::new(());
+        for _ in 0..1000 {
+            let mut small = SmallHoleVec::<(u8, Rc<()>), 4>::new();
+            let mut model = Model::new();
+
+            for _ in 0..100 {
+                let len = model.len();
+                assert_eq!(Rc::strong_count(&value), 1 + 2 * len);
+                let new_value = (rng.gen::<u8>(), value.clone());
+                match rng.gen::<u64>() % 14 {
+                    0 | 1 => {
+                        small.push_before_hole(new_value.clone());
+                        model.before_hole.push(new_value);
+                    }
+                    2 => {
+                        small.push_after_hole(new_value.clone());
+                        model.push_after_hole(new_value);
+                    }
+                    3 => assert_eq!(small.pop_before_hole(), model.before_hole.pop()),
+                    4 => assert_eq!(small.pop_after_hole(), model.pop_after_hole()),
+                    5 => {
+                        let hole_position = model.before_hole.len();
+                        let amount = rng.gen::<usize>() % (hole_position + 1);
+                        let drained = small.drain_before_hole(amount).collect::<Vec<_>>();
+                        assert_eq!(drained, model.drain(hole_position - amount, hole_position));
+                    }
+                    6 => {
+                        let amount = rng.gen::<usize>() % 4;
+                        let values = vec![new_value; amount];
+                        if rng.gen::<bool>() {
+                            small.extend_after_hole(values.iter().cloned());
+                            model.after_hole.splice(0..0, values);
+                        } else {
+                            small.extend_from_slice_before_hole(&values);
+                            model.before_hole.extend(values);
+                        }
+                    }
+                    7 => {
+                        let index = rng.gen::<usize>() % (len + 1);
+                        small.insert(index, new_value.clone());
+                        model.set_hole_position(index);
+                        model.before_hole.push(new_value);
+                    }
+                    8 => {
+                        small.shrink_to_fit();
+                        assert_eq!(small.spilled(), len > 4);
+                    }
+                    9 => {
+                        let min_capacity = rng.gen::<usize>() % 8;
+                        let spilled = small.spilled();
+                        small.shrink_to(min_capacity);
+                        assert_eq!(small.spilled(), spilled && len.max(min_capacity) > 4);
+                        assert!(small.capacity() >= min_capacity.min(4));
+                    }
+                    10 => {
+                        let additional = rng.gen::<usize>() % 8;
+                        if rng.gen::<bool>() {
+                            small.reserve_exact(additional);
+                        } else {
+                            assert_eq!(small.try_reserve(additional), Ok(()));
+                        }
+                        assert!(small.gap_len() >= additional);
+                        let overflow = small.try_reserve(usize::MAX);
+                        assert_eq!(overflow, Err(HoleVecError::CapacityOverflow));
+                    }
+                    11 => {
+                        let (start, end) = random_range(&mut rng, len);
+                        assert!(small.drain(start..end).eq(model.drain(start, end)));
+                    }
+                    12 => {
+                        let (start, end) = random_range(&mut rng, len);
+                        let values = vec![new_value; rng.gen::<usize>() % 4];
+                        let drained = model.drain(start, end);
+                        assert!(small.splice(start..end, values.clone()).eq(drained));
+                        model.before_hole.extend(values);
+                    }
+                    _ => {
+                        let position = rng.gen::<usize>() % (len + 2);
+                        let result = small.try_set_hole_position(position);
+                        assert_eq!(result, bounds_result(position, len));
+                        if result.is_ok() {
+                            model.set_hole_position(position);
+                        }
+                    }
+                }
+
+                assert_eq!(small.as_slices(), model.as_slices());
+                let values = model.values();
+                assert_eq!(small, values);
+                assert_values!(small, values);
+                assert!(small.capacity() >= values.len());
+                assert!(small.gap_len() <= small.capacity() - values.len());
+                assert!(small.spilled() || values.len() <= 4);
+                assert_eq!(small.get_mut(usize::MAX), None);
+                assert_eq!(small.last(), values.last());
+                assert_search!(small, values, &mut rng);
+                let bytes = values.iter().map(|value| value.0).collect::<Vec<_>>();
+                let mut byte_small = SmallHoleVec::<u8, 4>::new();
+                byte_small.extend_before_hole(bytes.iter().copied());
+                byte_small.set_hole_position(model.before_hole.len());
+                assert_search!(byte_small, bytes, &mut rng, bytes);
+
+                let clone = small.clone();
+                assert_eq!(clone.as_slices(), small.as_slices());
+                assert_into_iter!(clone.into_iter(), values, "SmallIntoIter");
+            }
+
+            let vec = small.into_hole_vec();
+            assert_eq!(vec.as_slices(), model.as_slices());
+            let small = SmallHoleVec::<_, 4>::from(vec);
+            assert_eq!(small.spilled(), model.len() > 4);
+            assert_eq!(small.as_slices(), model.as_slices());
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+}
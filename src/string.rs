@@ -0,0 +1,447 @@
+use alloc::string::String;
+use core::cmp::Ordering;
+use core::fmt::{self, Write};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::str;
+
+use crate::lines::LineIndex;
+use crate::{HoleVec, HoleVecError};
+
+// A gap buffer for text. The hole always sits on a char boundary, so the text
+// on either side of it is valid UTF-8 on its own. Positions, amounts and
+// columns are counted in bytes.
+#[derive(Clone, Default)]
+pub struct HoleString {
+    vec: HoleVec<u8>,
+    lines: LineIndex,
+}
+
+impl HoleString {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            vec: HoleVec::with_capacity(capacity),
+            lines: LineIndex::default(),
+        }
+    }
+
+    pub fn from_string(string: String, hole_position: usize) -> Self {
+        assert!(string.is_char_boundary(hole_position));
+        let mut lines = LineIndex::default();
+        lines.inserted_before_hole(0, &string.as_bytes()[..hole_position]);
+        lines.inserted_after_hole(0, &string.as_bytes()[hole_position..]);
+        Self {
+            vec: HoleVec::from_vec(string.into_bytes(), hole_position),
+            lines,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.vec.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vec.is_empty()
+    }
+
+    pub fn len_before_hole(&self) -> usize {
+        self.vec.len_before_hole()
+    }
+
+    pub fn len_after_hole(&self) -> usize {
+        self.vec.len_after_hole()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.vec.reserve(additional);
+    }
+
+    pub fn as_hole_vec(&self) -> &HoleVec<u8> {
+        &self.vec
+    }
+
+    pub fn into_hole_vec(self) -> HoleVec<u8> {
+        self.vec
+    }
+
+    pub fn is_char_boundary(&self, position: usize) -> bool {
+        match self.vec.get(position) {
+            // Continuation bytes look like `0b10xx_xxxx`
+            Some(&byte) => (byte as i8) >= -0x40,
+            None => position == self.len(),
+        }
+    }
+
+    pub fn push_char_before_hole(&mut self, c: char) {
+        self.push_str_before_hole(c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn push_char_after_hole(&mut self, c: char) {
+        self.push_str_after_hole(c.encode_utf8(&mut [0; 4]));
+    }
+
+    pub fn push_str_before_hole(&mut self, string: &str) {
+        self.lines
+            .inserted_before_hole(self.len_before_hole(), string.as_bytes());
+        self.vec.extend_from_slice_before_hole(string.as_bytes());
+    }
+
+    pub fn push_str_after_hole(&mut self, string: &str) {
+        self.lines
+            .inserted_after_hole(self.len_after_hole(), string.as_bytes());
+        self.vec.extend_from_slice_after_hole(string.as_bytes());
+    }
+
+    pub fn pop_char_before_hole(&mut self) -> Option<char> {
+        let c = self.as_str_before_hole().chars().next_back()?;
+        self.vec.drain_before_hole(c.len_utf8());
+        self.lines.removed_before_hole(self.len_before_hole());
+        Some(c)
+    }
+
+    pub fn pop_char_after_hole(&mut self) -> Option<char> {
+        let c = self.as_str_after_hole().chars().next()?;
+        self.vec.drain_after_hole(c.len_utf8());
+        self.lines.removed_after_hole(self.len_after_hole());
+        Some(c)
+    }
+
+    pub fn move_hole_right(&mut self, amount: usize) {
+        self.try_move_hole_right(amount)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn move_hole_left(&mut self, amount: usize) {
+        self.try_move_hole_left(amount)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn set_hole_position(&mut self, position: usize) {
+        self.try_set_hole_position(position)
+            .unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    pub fn try_move_hole_right(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        let position = self.len_before_hole().saturating_add(amount);
+        self.check_char_boundary(position)?;
+        self.vec.try_move_hole_right(amount)?;
+        self.lines
+            .moved_right(self.len_before_hole(), self.len_after_hole());
+        Ok(())
+    }
+
+    pub fn try_move_hole_left(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        let position = self.len_before_hole().saturating_sub(amount);
+        self.check_char_boundary(position)?;
+        self.vec.try_move_hole_left(amount)?;
+        self.lines
+            .moved_left(self.len_before_hole(), self.len_after_hole());
+        Ok(())
+    }
+
+    pub fn try_set_hole_position(&mut self, position: usize) -> Result<(), HoleVecError> {
+        self.check_char_boundary(position)?;
+        let hole_position = self.len_before_hole();
+        self.vec.try_set_hole_position(position)?;
+        if position > hole_position {
+            self.lines.moved_right(position, self.len_after_hole());
+        } else {
+            self.lines.moved_left(position, self.len_after_hole());
+        }
+        Ok(())
+    }
+
+    // Positions past the end are left for `HoleVec` to report
+    fn check_char_boundary(&self, position: usize) -> Result<(), HoleVecError> {
+        if position > self.len() || self.is_char_boundary(position) {
+            Ok(())
+        } else {
+            Err(HoleVecError::NotCharBoundary { position })
+        }
+    }
+
+    pub fn as_strs(&self) -> (&str, &str) {
+        (self.as_str_before_hole(), self.as_str_after_hole())
+    }
+
+    pub fn as_str_before_hole(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.vec.as_slices_before_hole()) }
+    }
+
+    pub fn as_str_after_hole(&self) -> &str {
+        unsafe { str::from_utf8_unchecked(self.vec.as_slices_after_hole()) }
+    }
+
+    pub fn chars(&self) -> impl DoubleEndedIterator<Item = char> + '_ {
+        let (before_hole, after_hole) = self.as_strs();
+        before_hole.chars().chain(after_hole.chars())
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.lines.newline_count() + 1
+    }
+
+    pub fn line_to_offset(&self, line: usize) -> Option<usize> {
+        match line.checked_sub(1) {
+            None => Some(0),
+            Some(newline) => Some(self.lines.newline(newline, self.len())? + 1),
+        }
+    }
+
+    // Returns the line containing `offset` and the column of `offset` within it
+    pub fn offset_to_line_col(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset > self.len() {
+            return None;
+        }
+        let line = self
+            .lines
+            .newlines_before(offset, self.len_before_hole(), self.len());
+        Some((line, offset - self.line_to_offset(line)?))
+    }
+
+    // Returns the text of `line` without its newline, split at the hole
+    pub fn line(&self, line: usize) -> Option<(&str, &str)> {
+        let start = self.line_to_offset(line)?;
+        let end = self.lines.newline(line, self.len()).unwrap_or(self.len());
+        let hole_position = self.len_before_hole();
+        let (before_hole, after_hole) = self.as_strs();
+        Some((
+            &before_hole[start.min(hole_position)..end.min(hole_position)],
+            &after_hole[start.saturating_sub(hole_position)..end.saturating_sub(hole_position)],
+        ))
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        (0..self.line_count()).map(move |line| self.line(line).expect("BUG"))
+    }
+}
+
+impl PartialEq for HoleString {
+    fn eq(&self, other: &Self) -> bool {
+        self.vec == other.vec
+    }
+}
+
+impl Eq for HoleString {}
+
+impl PartialOrd for HoleString {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HoleString {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.vec.cmp(&other.vec)
+    }
+}
+
+impl Hash for HoleString {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.vec.hash(state);
+    }
+}
+
+impl fmt::Display for HoleString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (before_hole, after_hole) = self.as_strs();
+        f.write_str(before_hole)?;
+        f.write_str(after_hole)
+    }
+}
+
+impl fmt::Debug for HoleString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_char('"')?;
+        for c in self.chars() {
+            // Escape quotes but not apostrophes, like `str` does
+            if c == '\'' {
+                f.write_char(c)?;
+            } else {
+                c.escape_debug().try_for_each(|c| f.write_char(c))?;
+            }
+        }
+        f.write_char('"')
+    }
+}
+
+impl Write for HoleString {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str_before_hole(s);
+        Ok(())
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.push_char_before_hole(c);
+        Ok(())
+    }
+}
+
+impl PartialEq<str> for HoleString {
+    fn eq(&self, other: &str) -> bool {
+        self.vec == other.as_bytes()
+    }
+}
+
+impl PartialEq<&str> for HoleString {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+impl PartialEq<String> for HoleString {
+    fn eq(&self, other: &String) -> bool {
+        *self == **other
+    }
+}
+
+impl Extend<char> for HoleString {
+    fn extend<I: IntoIterator<Item = char>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|c| self.push_char_before_hole(c));
+    }
+}
+
+impl<'a> Extend<&'a str> for HoleString {
+    fn extend<I: IntoIterator<Item = &'a str>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|s| self.push_str_before_hole(s));
+    }
+}
+
+impl FromIterator<char> for HoleString {
+    fn from_iter<I: IntoIterator<Item = char>>(iter: I) -> Self {
+        let mut string = Self::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl<'a> FromIterator<&'a str> for HoleString {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut string = Self::new();
+        string.extend(iter);
+        string
+    }
+}
+
+impl From<&str> for HoleString {
+    fn from(string: &str) -> Self {
+        let mut hole_string = Self::with_capacity(string.len());
+        hole_string.push_str_before_hole(string);
+        hole_string
+    }
+}
+
+impl From<String> for HoleString {
+    fn from(string: String) -> Self {
+        let len = string.len();
+        Self::from_string(string, len)
+    }
+}
+
+impl From<HoleString> for String {
+    fn from(string: HoleString) -> Self {
+        unsafe { String::from_utf8_unchecked(string.vec.into()) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+    use std::prelude::v1::*;
+
+    const CHARS: [char; 7] = ['a', 'z', 'é', 'ß', '€', '🦀', '\n'];
+
+    #[test]
+    fn it_works() {
+        let mut rng = thread_rng();
+        for _ in 0..100 {
+            let mut string = HoleString::new();
+            let mut before_hole = String::new();
+            let mut after_hole = String::new();
+
+            for _ in 0..1000 {
+                let c = CHARS[rng.gen::<usize>() % CHARS.len()];
+                match rng.gen::<u64>() % 7 {
+                    0 => {
+                        string.push_char_before_hole(c);
+                        before_hole.push(c);
+                    }
+                    1 => {
+                        string.push_char_after_hole(c);
+                        after_hole.insert(0, c);
+                    }
+                    2 => {
+                        assert_eq!(string.pop_char_before_hole(), before_hole.pop());
+                    }
+                    3 => {
+                        let expected = after_hole.chars().next();
+                        if let Some(c) = expected {
+                            after_hole.remove(0);
+                            assert_eq!(c.len_utf8(), string.len_after_hole() - after_hole.len());
+                        }
+                        assert_eq!(string.pop_char_after_hole(), expected);
+                    }
+                    4 => {
+                        write!(string, "{}{}", c, c).unwrap();
+                        before_hole.push(c);
+                        before_hole.push(c);
+                    }
+                    _ => {
+                        let position = rng.gen::<usize>() % (2 + string.len());
+                        let mut text = before_hole.clone() + &after_hole;
+                        let result = string.try_set_hole_position(position);
+                        if position > text.len() {
+                            let err = HoleVecError::OutOfBounds {
+                                requested: position,
+                                available: text.len(),
+                            };
+                            assert_eq!(result, Err(err));
+                        } else if !text.is_char_boundary(position) {
+                            let err = HoleVecError::NotCharBoundary { position };
+                            assert_eq!(result, Err(err));
+                        } else {
+                            assert_eq!(result, Ok(()));
+                            after_hole = text.split_off(position);
+                            before_hole = text;
+                        }
+                    }
+                }
+
+                assert_eq!(string.as_strs(), (&*before_hole, &*after_hole));
+                let text = before_hole.clone() + &after_hole;
+                assert_eq!(string, text);
+                assert_eq!(string.to_string(), text);
+                assert_eq!(format!("{:?}", string), format!("{:?}", text));
+                assert!(string.chars().eq(text.chars()));
+
+                let lines = text.split('\n').collect::<Vec<_>>();
+                assert_eq!(string.line_count(), lines.len());
+                let mut offset = 0;
+                for (index, line) in lines.iter().enumerate() {
+                    let (a0, a1) = string.line(index).unwrap();
+                    assert_eq!(a0.to_string() + a1, *line);
+                    assert_eq!(string.line_to_offset(index), Some(offset));
+                    for column in 0..=line.len() {
+                        let position = Some((index, column));
+                        assert_eq!(string.offset_to_line_col(offset + column), position);
+                    }
+                    offset += line.len() + 1;
+                }
+                assert_eq!(string.line_to_offset(lines.len()), None);
+                assert_eq!(string.line(lines.len()), None);
+                assert_eq!(string.offset_to_line_col(text.len() + 1), None);
+                assert_eq!(string.lines().count(), lines.len());
+            }
+            assert_eq!(String::from(string), before_hole + &after_hole);
+        }
+    }
+}
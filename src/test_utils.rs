@@ -0,0 +1,158 @@
+// Helpers shared by the randomized tests of `HoleArray`, `SmallHoleVec` and
+// `MultiHoleVec`, which check a collection against plain `Vec`s after every
+// random operation.
+use rand::Rng;
+use std::prelude::v1::*;
+
+use crate::HoleVecError;
+
+// The values a collection with a single hole should hold
+pub(crate) struct Model<T> {
+    pub(crate) before_hole: Vec<T>,
+    pub(crate) after_hole: Vec<T>,
+}
+
+impl<T: Clone> Model<T> {
+    pub(crate) fn new() -> Self {
+        Self {
+            before_hole: Vec::new(),
+            after_hole: Vec::new(),
+        }
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.before_hole.len() + self.after_hole.len()
+    }
+
+    pub(crate) fn values(&self) -> Vec<T> {
+        self.before_hole
+            .iter()
+            .chain(&self.after_hole)
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn as_slices(&self) -> (&[T], &[T]) {
+        (&self.before_hole, &self.after_hole)
+    }
+
+    pub(crate) fn push_after_hole(&mut self, value: T) {
+        self.after_hole.insert(0, value);
+    }
+
+    pub(crate) fn pop_after_hole(&mut self) -> Option<T> {
+        (!self.after_hole.is_empty()).then(|| self.after_hole.remove(0))
+    }
+
+    pub(crate) fn set_hole_position(&mut self, position: usize) {
+        self.before_hole.append(&mut self.after_hole);
+        self.after_hole = self.before_hole.split_off(position);
+    }
+
+    // Removes the values in `start..end`, leaving the hole at `start`
+    pub(crate) fn drain(&mut self, start: usize, end: usize) -> Vec<T> {
+        self.set_hole_position(end);
+        self.before_hole.split_off(start)
+    }
+}
+
+// Returns a random range within `0..len`
+pub(crate) fn random_range(rng: &mut impl Rng, len: usize) -> (usize, usize) {
+    let end = rng.gen::<usize>() % (len + 1);
+    (rng.gen::<usize>() % (end + 1), end)
+}
+
+// The result of a bounds checked operation that asks for `requested` values
+// where only `available` are left
+pub(crate) fn bounds_result(requested: usize, available: usize) -> Result<(), HoleVecError> {
+    if requested > available {
+        Err(HoleVecError::OutOfBounds {
+            requested,
+            available,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+// Checks iteration, indexing and `Debug` of `$collection` against `$values`
+macro_rules! assert_values {
+    ($collection:expr, $values:expr) => {{
+        let (collection, values) = (&$collection, &$values[..]);
+        assert_eq!(collection.len(), values.len());
+        assert!(collection.iter().eq(values));
+        assert!(collection.iter().rev().eq(values.iter().rev()));
+        for (index, value) in values.iter().enumerate() {
+            assert_eq!(&collection[index], value);
+        }
+        assert_eq!(collection.get(values.len()), None);
+        assert_eq!(
+            std::format!("{:?}", collection),
+            std::format!("{:?}", values)
+        );
+    }};
+}
+
+// Checks the searches of `$collection` against `$values`, with a needle cut
+// out of `$values` at random
+macro_rules! assert_search {
+    ($collection:expr, $values:expr, $rng:expr) => {{
+        let (collection, values) = (&$collection, &$values[..]);
+        let (start, end) = $crate::test_utils::random_range($rng, values.len());
+        let needle = &values[start..end];
+        let first = (0..=values.len()).find(|&i| values[i..].starts_with(needle));
+        let last = (0..=values.len()).rfind(|&i| values[i..].starts_with(needle));
+        assert_eq!(collection.find(needle), first);
+        assert_eq!(collection.rfind(needle), last);
+        assert_eq!(collection.find_iter(needle).next(), first);
+        assert!(collection
+            .find_iter(needle)
+            .all(|i| values[i..].starts_with(needle)));
+        if let Some(value) = values.last() {
+            assert!(collection.contains(value));
+            assert_eq!(
+                collection.position(|v| v == value),
+                values.iter().position(|v| v == value)
+            );
+        }
+    }};
+    // For collections of bytes, also checks that the `memchr` based search
+    // agrees with the generic one
+    ($collection:expr, $values:expr, $rng:expr, bytes) => {{
+        assert_search!($collection, $values, $rng);
+        #[cfg(feature = "memchr")]
+        {
+            let (collection, values) = (&$collection, &$values[..]);
+            let (start, end) = $crate::test_utils::random_range($rng, values.len());
+            // The reversed needle is usually not found
+            let reversed = values[start..end]
+                .iter()
+                .rev()
+                .copied()
+                .collect::<Vec<u8>>();
+            for needle in [&values[start..end], &reversed[..]] {
+                assert_eq!(collection.find_bytes(needle), collection.find(needle));
+                assert_eq!(collection.rfind_bytes(needle), collection.rfind(needle));
+                assert!(collection
+                    .find_bytes_iter(needle)
+                    .eq(collection.find_iter(needle)));
+            }
+        }
+    }};
+}
+
+// Checks a by-value iterator over `$values`, whose `Debug` output is wrapped
+// in `$name`. The last value is taken first, so both ends get used.
+macro_rules! assert_into_iter {
+    ($into_iter:expr, $values:expr, $name:literal) => {{
+        let (mut into_iter, values) = ($into_iter, &$values[..]);
+        let rest = &values[..values.len().saturating_sub(1)];
+        assert_eq!(into_iter.len(), values.len());
+        assert_eq!(into_iter.next_back().as_ref(), values.last());
+        let debug = std::format!("{:?}", into_iter.clone());
+        assert_eq!(debug, std::format!(concat!($name, "({:?})"), rest));
+        assert!(into_iter.eq(rest.iter().cloned()));
+    }};
+}
+
+pub(crate) use {assert_into_iter, assert_search, assert_values};
@@ -0,0 +1,2251 @@
+use alloc::boxed::Box;
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::mem::{self, ManuallyDrop};
+use core::ops::{Index, IndexMut, Range, RangeBounds};
+use core::ptr;
+use core::slice;
+
+use allocator_api2::alloc::{Allocator, Global};
+
+use crate::cursor::{Cursor, CursorMut};
+use crate::error::{check_bounds, range_bounds};
+use crate::iter::{Drain, Iter, IterMut};
+use crate::mark::{Gravity, Mark, Marks};
+use crate::raw::{self, RawBuf};
+use crate::search::{self, FindIter};
+use crate::slice::{HoleSlice, HoleSliceMut};
+use crate::HoleVecError;
+
+// The values before the hole are stored from `head` onwards and the values
+// after the hole up to `tail`, with the unused capacity between them forming
+// the hole. The slots in front of `head` and behind `tail` are spare room for
+// `push_front` and `push_back`, which is handed to the hole once it runs out.
+pub struct HoleVec<T, A: Allocator = Global> {
+    buf: RawBuf<T, A>,
+    // Index of the first value in `buf`
+    head: usize,
+    // Index in `buf` just past the last value
+    tail: usize,
+    // Amount of values before the hole
+    hole_position: usize,
+    // Amount of values after the hole
+    after_hole: usize,
+    growth_policy: GrowthPolicy,
+    marks: Marks,
+}
+
+// How much the capacity of a `HoleVec` grows when the hole fills up. The
+// capacity always grows by at least the amount that is needed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GrowthPolicy {
+    // Double the capacity
+    #[default]
+    Doubling,
+    // Grow the capacity by a fixed amount of values
+    Fixed(usize),
+    // Double the capacity, but never make the hole larger than this
+    MaxGap(usize),
+}
+
+impl<T> Default for HoleVec<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> HoleVec<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_in(capacity, Global)
+    }
+
+    pub fn try_with_capacity(capacity: usize) -> Result<Self, HoleVecError> {
+        Self::try_with_capacity_in(capacity, Global)
+    }
+
+    pub fn from_vec(vec: Vec<T>, hole_position: usize) -> Self {
+        assert!(hole_position <= vec.len());
+        let mut vec = ManuallyDrop::new(vec);
+        let (ptr, len, capacity) = (vec.as_mut_ptr(), vec.len(), vec.capacity());
+        let buf = unsafe { RawBuf::from_raw_parts(ptr, capacity) };
+        let mut hole_vec = Self {
+            head: 0,
+            tail: buf.capacity(),
+            buf,
+            hole_position: len,
+            after_hole: 0,
+            growth_policy: GrowthPolicy::default(),
+            marks: Marks::default(),
+        };
+        hole_vec.move_hole_left(len - hole_position);
+        hole_vec
+    }
+
+    pub fn from_vec_deque(vec: VecDeque<T>, hole_position: usize) -> Self {
+        Self::from_vec(vec.into(), hole_position)
+    }
+
+    // Closes the hole and hands the buffer over to a `Vec` without copying the
+    // values anywhere else
+    pub fn into_vec(self) -> Vec<T> {
+        Vec::from(self)
+    }
+
+    pub fn into_boxed_slice(self) -> Box<[T]> {
+        self.into_vec().into_boxed_slice()
+    }
+}
+
+impl<T, A: Allocator> HoleVec<T, A> {
+    pub fn new_in(alloc: A) -> Self {
+        Self::with_capacity_in(0, alloc)
+    }
+
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Self::from_buf(RawBuf::with_capacity_in(capacity, alloc))
+    }
+
+    pub fn try_with_capacity_in(capacity: usize, alloc: A) -> Result<Self, HoleVecError> {
+        Ok(Self::from_buf(RawBuf::try_with_capacity_in(
+            capacity, alloc,
+        )?))
+    }
+
+    fn from_buf(buf: RawBuf<T, A>) -> Self {
+        Self {
+            head: 0,
+            tail: buf.capacity(),
+            buf,
+            hole_position: 0,
+            after_hole: 0,
+            growth_policy: GrowthPolicy::default(),
+            marks: Marks::default(),
+        }
+    }
+
+    pub fn allocator(&self) -> &A {
+        self.buf.allocator()
+    }
+
+    // Hands the buffer over to the caller along with the ranges of the values
+    // before and after the hole, which are then owned by the caller
+    pub(crate) fn into_raw_parts(mut self) -> (RawBuf<T, A>, Range<usize>, Range<usize>) {
+        drop(mem::take(&mut self.marks));
+        let hole_vec = ManuallyDrop::new(self);
+        let buf = unsafe { ptr::read(&hole_vec.buf) };
+        let (head, tail) = (hole_vec.head, hole_vec.tail);
+        (
+            buf,
+            head..head + hole_vec.hole_position,
+            tail - hole_vec.after_hole..tail,
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.hole_position + self.after_hole
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len_before_hole(&self) -> usize {
+        self.hole_position
+    }
+
+    pub fn len_after_hole(&self) -> usize {
+        self.after_hole
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.buf.capacity()
+    }
+
+    // The room in the hole, which takes values without moving any others. The
+    // spare room that `push_front` and `push_back` keep at the ends of the
+    // buffer is not part of it.
+    pub fn gap_len(&self) -> usize {
+        self.tail - self.head - self.len()
+    }
+
+    // The spare capacity, in the hole as well as at the ends of the buffer
+    fn spare_len(&self) -> usize {
+        self.capacity() - self.len()
+    }
+
+    pub fn growth_policy(&self) -> GrowthPolicy {
+        self.growth_policy
+    }
+
+    pub fn set_growth_policy(&mut self, growth_policy: GrowthPolicy) {
+        self.growth_policy = growth_policy;
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        if additional > self.gap_len() {
+            self.make_room(additional);
+        }
+    }
+
+    pub fn try_reserve(&mut self, additional: usize) -> Result<(), HoleVecError> {
+        if additional > self.gap_len() {
+            self.try_make_room(additional)?;
+        }
+        Ok(())
+    }
+
+    pub fn reserve_exact(&mut self, additional: usize) {
+        self.try_reserve_exact(additional)
+            .unwrap_or_else(|err| raw::handle_error(err))
+    }
+
+    pub fn try_reserve_exact(&mut self, additional: usize) -> Result<(), HoleVecError> {
+        if additional > self.spare_len() {
+            let capacity = self
+                .len()
+                .checked_add(additional)
+                .ok_or(HoleVecError::CapacityOverflow)?;
+            self.try_set_capacity(capacity)?;
+        } else if additional > self.gap_len() {
+            self.relocate(0, self.capacity());
+        }
+        Ok(())
+    }
+
+    pub fn shrink_to_fit(&mut self) {
+        self.shrink_to(0);
+    }
+
+    pub fn shrink_to(&mut self, min_capacity: usize) {
+        let capacity = min_capacity.max(self.len());
+        if capacity < self.capacity() {
+            self.try_set_capacity(capacity)
+                .unwrap_or_else(|err| raw::handle_error(err))
+        }
+    }
+
+    #[cold]
+    fn make_room(&mut self, additional: usize) {
+        self.try_make_room(additional)
+            .unwrap_or_else(|err| raw::handle_error(err))
+    }
+
+    // Makes room for `additional` values in the hole, taking back the spare
+    // room at the ends before growing the buffer
+    fn try_make_room(&mut self, additional: usize) -> Result<(), HoleVecError> {
+        if additional <= self.spare_len() {
+            self.relocate(0, self.capacity());
+            Ok(())
+        } else {
+            self.try_grow(additional)
+        }
+    }
+
+    #[cold]
+    fn grow(&mut self, additional: usize) {
+        self.try_grow(additional)
+            .unwrap_or_else(|err| raw::handle_error(err))
+    }
+
+    fn try_grow(&mut self, additional: usize) -> Result<(), HoleVecError> {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .ok_or(HoleVecError::CapacityOverflow)?;
+        let doubled = self.capacity().saturating_mul(2).max(4);
+        let capacity = match self.growth_policy {
+            GrowthPolicy::Doubling => doubled,
+            GrowthPolicy::Fixed(amount) => self.capacity().saturating_add(amount),
+            GrowthPolicy::MaxGap(max_gap) => doubled.min(self.len().saturating_add(max_gap)),
+        };
+        self.try_set_capacity(capacity.max(required))
+    }
+
+    // Reallocates the buffer, with the values before the hole at its start and
+    // the values after the hole at its end.
+    fn try_set_capacity(&mut self, capacity: usize) -> Result<(), HoleVecError> {
+        debug_assert!(capacity >= self.len());
+        let old_capacity = self.capacity();
+        if capacity < old_capacity {
+            self.relocate(0, capacity);
+        }
+        self.buf.try_resize(capacity)?;
+        if capacity > old_capacity {
+            self.relocate(0, capacity);
+        }
+        Ok(())
+    }
+
+    // Moves the values before the hole to start at `head` and the values after
+    // the hole to end at `tail`
+    fn relocate(&mut self, head: usize, tail: usize) {
+        debug_assert!(head + self.len() <= tail && tail <= self.capacity());
+        let ptr = self.buf.ptr();
+        let (hole_position, after_hole) = (self.hole_position, self.after_hole);
+        let (old_head, old_tail) = (self.head, self.tail);
+        unsafe {
+            let move_before_hole = || ptr::copy(ptr.add(old_head), ptr.add(head), hole_position);
+            let move_after_hole = || {
+                ptr::copy(
+                    ptr.add(old_tail - after_hole),
+                    ptr.add(tail - after_hole),
+                    after_hole,
+                )
+            };
+            // The side that moves away from the other one goes first, so that
+            // neither overwrites values of the other
+            if head <= old_head {
+                move_before_hole();
+                move_after_hole();
+            } else {
+                move_after_hole();
+                move_before_hole();
+            }
+        }
+        self.head = head;
+        self.tail = tail;
+    }
+
+    // Makes room at the front of the buffer if `front` is set and at its back
+    // otherwise. Half of the spare capacity goes there, so that moving the
+    // values is paid for by the pushes that the room allows. The buffer grows
+    // first if that would not leave enough room compared to the length.
+    #[cold]
+    fn make_room_at_end(&mut self, front: bool) {
+        if self.spare_len() <= self.len() / 2 {
+            self.grow(1);
+        }
+        let spare = self.spare_len();
+        let mut head = self.head.min(spare / 4);
+        let mut back = (self.capacity() - self.tail).min(spare / 4);
+        if front {
+            head = spare - spare / 2;
+        } else {
+            back = spare - spare / 2;
+        }
+        self.relocate(head, self.capacity() - back);
+    }
+
+    fn before_hole_ptr(&self) -> *mut T {
+        unsafe { self.buf.ptr().add(self.head) }
+    }
+
+    fn after_hole_ptr(&self) -> *mut T {
+        unsafe { self.buf.ptr().add(self.tail - self.after_hole) }
+    }
+
+    pub fn push_before_hole(&mut self, value: T) {
+        self.reserve(1);
+        unsafe { self.before_hole_ptr().add(self.hole_position).write(value) };
+        self.hole_position += 1;
+    }
+
+    pub fn push_after_hole(&mut self, value: T) {
+        self.reserve(1);
+        self.after_hole += 1;
+        unsafe { self.after_hole_ptr().write(value) };
+    }
+
+    pub fn pop_before_hole(&mut self) -> Option<T> {
+        (self.len_before_hole() > 0).then(|| {
+            self.hole_position -= 1;
+            self.removed_before_hole(1);
+            unsafe { self.before_hole_ptr().add(self.hole_position).read() }
+        })
+    }
+
+    pub fn pop_after_hole(&mut self) -> Option<T> {
+        (self.len_after_hole() > 0).then(|| {
+            let value = unsafe { self.after_hole_ptr().read() };
+            self.after_hole -= 1;
+            self.removed_after_hole(1);
+            value
+        })
+    }
+
+    // Inserts `value` at the start without moving the hole, which stays
+    // between the same values and so ends up one position further along.
+    //
+    // The front and back operations use spare room at the ends of the buffer,
+    // so they take amortized O(1) time wherever the hole is. They re-place the
+    // marks on their side of the hole though, so with marks there they take
+    // time linear in the amount of those marks.
+    pub fn push_front(&mut self, value: T) {
+        if self.head == 0 {
+            self.make_room_at_end(true);
+        }
+        self.head -= 1;
+        unsafe { self.before_hole_ptr().write(value) };
+        self.hole_position += 1;
+        if !self.marks.is_empty() {
+            self.marks
+                .inserted_front(1, self.hole_position, self.after_hole);
+        }
+    }
+
+    // Inserts `value` at the end without moving the hole
+    pub fn push_back(&mut self, value: T) {
+        if self.tail == self.capacity() {
+            self.make_room_at_end(false);
+        }
+        unsafe { self.buf.ptr().add(self.tail).write(value) };
+        self.tail += 1;
+        self.after_hole += 1;
+        if !self.marks.is_empty() {
+            self.marks
+                .inserted_back(1, self.hole_position, self.after_hole);
+        }
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.hole_position == 0 {
+            return self.pop_after_hole();
+        }
+        let value = unsafe { self.before_hole_ptr().read() };
+        self.head += 1;
+        self.hole_position -= 1;
+        if !self.marks.is_empty() {
+            self.marks
+                .removed_front(1, self.hole_position, self.after_hole);
+        }
+        Some(value)
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.after_hole == 0 {
+            return self.pop_before_hole();
+        }
+        self.tail -= 1;
+        self.after_hole -= 1;
+        let value = unsafe { self.buf.ptr().add(self.tail).read() };
+        if !self.marks.is_empty() {
+            self.marks
+                .removed_back(1, self.hole_position, self.after_hole);
+        }
+        Some(value)
+    }
+
+    pub fn extend_before_hole<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+        iter.for_each(|value| self.push_before_hole(value));
+    }
+
+    pub fn extend_after_hole<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut iter = iter.into_iter();
+        let (lower, upper) = iter.size_hint();
+        if upper != Some(lower) {
+            // Without an exact length the values cannot be written to their
+            // final place up front, so they are pushed in front of the hole
+            // and then moved across it as one block.
+            let len_before_hole = self.len_before_hole();
+            self.extend_before_hole(iter);
+            self.move_hole_left(self.len_before_hole() - len_before_hole);
+            return;
+        }
+
+        self.reserve(lower);
+        let start = unsafe { self.after_hole_ptr().sub(lower) };
+        let mut written = 0;
+        for value in iter.by_ref().take(lower) {
+            unsafe { start.add(written).write(value) };
+            written += 1;
+        }
+        if written < lower {
+            unsafe { ptr::copy(start, self.after_hole_ptr().sub(written), written) };
+        }
+        self.after_hole += written;
+
+        // The size hint is not trusted, so any values beyond it still have to
+        // end up behind the ones written so far
+        let rest = iter.collect::<Vec<T>>();
+        if !rest.is_empty() {
+            self.move_hole_right(written);
+            self.extend_after_hole(rest);
+            self.move_hole_left(written);
+        }
+    }
+
+    pub fn extend_from_slice_before_hole(&mut self, values: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(values.len());
+        for value in values {
+            unsafe { self.before_hole_ptr().add(self.hole_position).write(value.clone()) };
+            self.hole_position += 1;
+        }
+    }
+
+    // The values are cloned back to front, so that each one is part of the
+    // `HoleVec` as soon as it has been written
+    pub fn extend_from_slice_after_hole(&mut self, values: &[T])
+    where
+        T: Clone,
+    {
+        self.reserve(values.len());
+        for value in values.iter().rev() {
+            unsafe { self.after_hole_ptr().sub(1).write(value.clone()) };
+            self.after_hole += 1;
+        }
+    }
+
+    pub fn drain_before_hole(&mut self, amount: usize) -> Drain<'_, T> {
+        assert!(amount <= self.len_before_hole());
+        // The drained values become part of the hole straight away, so the
+        // `HoleVec` is left consistent even if the `Drain` is leaked.
+        self.hole_position -= amount;
+        self.removed_before_hole(amount);
+        unsafe { Drain::new(self.before_hole_ptr().add(self.hole_position), amount) }
+    }
+
+    pub fn drain_after_hole(&mut self, amount: usize) -> Drain<'_, T> {
+        assert!(amount <= self.len_after_hole());
+        let start = self.after_hole_ptr();
+        self.after_hole -= amount;
+        self.removed_after_hole(amount);
+        unsafe { Drain::new(start, amount) }
+    }
+
+    // Keeps the marks in sync after values next to the hole were removed.
+    // Inserting values at the hole never requires updating any marks.
+    fn removed_before_hole(&mut self, amount: usize) {
+        if !self.marks.is_empty() {
+            self.marks
+                .removed_before_hole(amount, self.hole_position, self.after_hole);
+        }
+    }
+
+    fn removed_after_hole(&mut self, amount: usize) {
+        if !self.marks.is_empty() {
+            self.marks
+                .removed_after_hole(amount, self.hole_position, self.after_hole);
+        }
+    }
+
+    pub fn add_mark(&mut self, position: usize, gravity: Gravity) -> Mark {
+        assert!(position <= self.len());
+        self.marks
+            .add(position, gravity, self.hole_position, self.after_hole)
+    }
+
+    pub fn remove_mark(&mut self, mark: Mark) -> Option<usize> {
+        self.marks.remove(mark, self.len())
+    }
+
+    pub fn mark_position(&self, mark: Mark) -> Option<usize> {
+        self.marks.position(mark, self.len())
+    }
+
+    pub fn mark_gravity(&self, mark: Mark) -> Option<Gravity> {
+        self.marks.gravity(mark)
+    }
+
+    pub fn insert(&mut self, index: usize, value: T) {
+        self.set_hole_position(index);
+        self.push_before_hole(value);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let len = self.len();
+        assert!(
+            index < len,
+            "removal index (is {}) should be < len (is {})",
+            index,
+            len
+        );
+        self.set_hole_position(index);
+        self.pop_after_hole().unwrap()
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T> {
+        let (start, end) = range_bounds(range, self.len());
+        self.set_hole_position(start);
+        self.drain_after_hole(end - start)
+    }
+
+    // Unlike `Vec::splice`, the replacement values are inserted right away
+    // rather than when the returned iterator is dropped.
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Drain<'_, T>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = range_bounds(range, self.len());
+        self.set_hole_position(start);
+        if !self.marks.is_empty() {
+            // Marks in the range should end up in front of the replacement
+            // values if they have left gravity, as if the range had been
+            // removed before inserting them.
+            self.marks
+                .collapse_left_gravity(end - start, self.hole_position, self.after_hole);
+        }
+        self.extend_before_hole(replace_with);
+        self.drain_after_hole(end - start)
+    }
+
+    pub fn move_hole_right(&mut self, amount: usize) {
+        assert!(amount <= self.len_after_hole());
+        unsafe {
+            ptr::copy(
+                self.after_hole_ptr(),
+                self.before_hole_ptr().add(self.hole_position),
+                amount,
+            );
+        }
+        self.hole_position += amount;
+        self.after_hole -= amount;
+        if !self.marks.is_empty() {
+            self.marks
+                .moved_right(amount, self.hole_position, self.after_hole);
+        }
+    }
+
+    pub fn move_hole_left(&mut self, amount: usize) {
+        assert!(amount <= self.len_before_hole());
+        self.hole_position -= amount;
+        self.after_hole += amount;
+        unsafe {
+            ptr::copy(
+                self.before_hole_ptr().add(self.hole_position),
+                self.after_hole_ptr(),
+                amount,
+            );
+        }
+        if !self.marks.is_empty() {
+            self.marks
+                .moved_left(amount, self.hole_position, self.after_hole);
+        }
+    }
+
+    pub fn set_hole_position(&mut self, position: usize) {
+        assert!(position <= self.len());
+        if position > self.hole_position {
+            self.move_hole_right(position - self.hole_position);
+        } else {
+            self.move_hole_left(self.hole_position - position);
+        }
+    }
+
+    pub fn try_move_hole_right(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        check_bounds(amount, self.len_after_hole())?;
+        self.move_hole_right(amount);
+        Ok(())
+    }
+
+    pub fn try_move_hole_left(&mut self, amount: usize) -> Result<(), HoleVecError> {
+        check_bounds(amount, self.len_before_hole())?;
+        self.move_hole_left(amount);
+        Ok(())
+    }
+
+    pub fn try_set_hole_position(&mut self, position: usize) -> Result<(), HoleVecError> {
+        check_bounds(position, self.len())?;
+        self.set_hole_position(position);
+        Ok(())
+    }
+
+    pub fn cursor(&self) -> Cursor<'_, T, A> {
+        Cursor::new(self, self.hole_position)
+    }
+
+    pub fn cursor_mut(&mut self) -> CursorMut<'_, T, A> {
+        CursorMut::new(self)
+    }
+
+    // Maps a logical index to an index into `buf`.
+    fn physical_index(&self, index: usize) -> usize {
+        if index < self.hole_position {
+            self.head + index
+        } else {
+            self.tail - self.len() + index
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        (index < self.len()).then(|| unsafe { &*self.buf.ptr().add(self.physical_index(index)) })
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index < self.len() {
+            Some(unsafe { &mut *self.buf.ptr().add(self.physical_index(index)) })
+        } else {
+            None
+        }
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.get(0)
+    }
+
+    pub fn first_mut(&mut self) -> Option<&mut T> {
+        self.get_mut(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.len().checked_sub(1).and_then(|index| self.get(index))
+    }
+
+    pub fn last_mut(&mut self) -> Option<&mut T> {
+        self.len()
+            .checked_sub(1)
+            .and_then(move |index| self.get_mut(index))
+    }
+
+    pub fn as_slices(&self) -> (&[T], &[T]) {
+        (self.as_slices_before_hole(), self.as_slices_after_hole())
+    }
+
+    pub fn as_mut_slices(&mut self) -> (&mut [T], &mut [T]) {
+        unsafe {
+            (
+                slice::from_raw_parts_mut(self.before_hole_ptr(), self.hole_position),
+                slice::from_raw_parts_mut(self.after_hole_ptr(), self.after_hole),
+            )
+        }
+    }
+
+    // Closes the hole by moving the shorter side up against the other one,
+    // and returns all values as one slice. The hole keeps its index, and its
+    // room joins the spare room at the ends of the buffer.
+    pub fn make_contiguous(&mut self) -> &mut [T] {
+        let len = self.len();
+        if self.after_hole <= self.hole_position {
+            self.relocate(self.head, self.head + len);
+        } else {
+            self.relocate(self.tail - len, self.tail);
+        }
+        unsafe { slice::from_raw_parts_mut(self.before_hole_ptr(), len) }
+    }
+
+    // A view of the values in `range`, without moving the hole
+    pub fn range<R: RangeBounds<usize>>(&self, range: R) -> HoleSlice<'_, T> {
+        HoleSlice::new(self.as_slices(), range)
+    }
+
+    pub fn range_mut<R: RangeBounds<usize>>(&mut self, range: R) -> HoleSliceMut<'_, T> {
+        HoleSliceMut::new(self.as_mut_slices(), range)
+    }
+
+    pub fn as_slices_before_hole(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.before_hole_ptr(), self.hole_position) }
+    }
+
+    pub fn as_slices_after_hole(&self) -> &[T] {
+        unsafe { slice::from_raw_parts(self.after_hole_ptr(), self.after_hole) }
+    }
+
+    pub fn as_mut_slices_before_hole(&mut self) -> &mut [T] {
+        self.as_mut_slices().0
+    }
+
+    pub fn as_mut_slices_after_hole(&mut self) -> &mut [T] {
+        self.as_mut_slices().1
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter::new(self.as_slices())
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T> {
+        IterMut::new(self.as_mut_slices())
+    }
+
+    pub fn iter_before_hole(&self) -> Iter<'_, T> {
+        Iter::new((self.as_slices_before_hole(), &[]))
+    }
+
+    pub fn iter_after_hole(&self) -> Iter<'_, T> {
+        Iter::new((&[], self.as_slices_after_hole()))
+    }
+
+    pub fn position<P: FnMut(&T) -> bool>(&self, predicate: P) -> Option<usize> {
+        self.iter().position(predicate)
+    }
+
+    // Empties the `HoleVec` without dropping its values, which have been
+    // moved elsewhere. Marks collapse onto the start.
+    fn forget_values(&mut self) {
+        let (hole_position, after_hole) = (self.hole_position, self.after_hole);
+        self.hole_position = 0;
+        self.removed_before_hole(hole_position);
+        self.after_hole = 0;
+        self.removed_after_hole(after_hole);
+    }
+}
+
+impl<T, A: Allocator + Clone> HoleVec<T, A> {
+    // Splits off the values after the hole into a new `HoleVec` with its hole
+    // at the start. Only the smaller side is copied into a new allocation.
+    // Marks after the hole collapse onto it and stay with `self`.
+    pub fn split_at_hole(&mut self) -> Self {
+        let (hole_position, after_hole) = (self.hole_position, self.after_hole);
+        let mut other;
+        if after_hole <= hole_position {
+            other = Self::with_capacity_in(after_hole, self.allocator().clone());
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    self.after_hole_ptr(),
+                    other.buf.ptr().add(other.capacity() - after_hole),
+                    after_hole,
+                );
+            }
+        } else {
+            other = Self::with_capacity_in(hole_position, self.allocator().clone());
+            unsafe {
+                ptr::copy_nonoverlapping(self.before_hole_ptr(), other.buf.ptr(), hole_position)
+            };
+            mem::swap(&mut self.buf, &mut other.buf);
+            mem::swap(&mut self.head, &mut other.head);
+            mem::swap(&mut self.tail, &mut other.tail);
+        }
+        other.after_hole = after_hole;
+        other.growth_policy = self.growth_policy;
+        self.after_hole = 0;
+        self.removed_after_hole(after_hole);
+        other
+    }
+
+    // Moves all values of `other` to the end, after the hole, leaving `other`
+    // empty. With a zero-sized allocator such as `Global`, the values of
+    // whichever side needs fewer moves are copied into the buffer of the
+    // other side. Taking over the buffer of `other` would also take over its
+    // allocator though, which then has to free it. So with any other
+    // allocator the values of `other` are always copied into `self`, and the
+    // work is proportional to the length of `other` rather than to that of
+    // the smaller side.
+    pub fn append(&mut self, other: &mut Self) {
+        let len = other.len();
+        if len == 0 {
+            return;
+        }
+
+        let stateless = mem::size_of::<A>() == 0;
+        if stateless && self.len() + other.hole_position < self.after_hole + len {
+            // Open up the hole of `other` at its start and move all of our
+            // values into it
+            other.move_hole_left(other.hole_position);
+            other.reserve(self.len());
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    self.before_hole_ptr(),
+                    other.before_hole_ptr(),
+                    self.hole_position,
+                );
+                ptr::copy_nonoverlapping(
+                    self.after_hole_ptr(),
+                    other.after_hole_ptr().sub(self.after_hole),
+                    self.after_hole,
+                );
+            }
+            mem::swap(&mut self.buf, &mut other.buf);
+            mem::swap(&mut self.head, &mut other.head);
+            mem::swap(&mut self.tail, &mut other.tail);
+        } else {
+            // Make room at the end, moving the values only as far as needed,
+            // and copy the values of `other` there
+            if self.capacity() - self.tail < len {
+                if len > self.spare_len() {
+                    self.grow(len);
+                }
+                let tail = self.capacity() - len;
+                self.relocate(self.head.min(tail - self.len()), tail);
+            }
+            unsafe {
+                let end = self.buf.ptr().add(self.tail);
+                let (before_hole, after_hole) = other.as_slices();
+                ptr::copy_nonoverlapping(before_hole.as_ptr(), end, before_hole.len());
+                ptr::copy_nonoverlapping(
+                    after_hole.as_ptr(),
+                    end.add(before_hole.len()),
+                    after_hole.len(),
+                );
+            }
+            self.tail += len;
+        }
+
+        self.after_hole += len;
+        other.forget_values();
+        if !self.marks.is_empty() {
+            self.marks
+                .inserted_back(len, self.hole_position, self.after_hole);
+        }
+    }
+
+    // Splits off the values from `at` onwards, like `Vec::split_off`. The hole
+    // is moved to `at` first, so it ends up at the end of `self` and at the
+    // start of the returned `HoleVec`.
+    pub fn split_off(&mut self, at: usize) -> Self {
+        assert!(at <= self.len(), "split index {} is out of bounds", at);
+        self.set_hole_position(at);
+        self.split_at_hole()
+    }
+}
+
+impl<T: PartialEq, A: Allocator> HoleVec<T, A> {
+    pub fn contains(&self, value: &T) -> bool {
+        let (a0, a1) = self.as_slices();
+        a0.contains(value) || a1.contains(value)
+    }
+
+    // Compares the needle against every window, so it takes O(n * m) time.
+    // This holds for `HoleVec<u8>` too: only `find_bytes`, `rfind_bytes` and
+    // `find_bytes_iter` use `memchr`, so byte searches should call those.
+    pub fn find(&self, needle: &[T]) -> Option<usize> {
+        search::find_from(self.as_slices(), needle, 0, search::find_in_slice)
+    }
+
+    pub fn rfind(&self, needle: &[T]) -> Option<usize> {
+        search::rfind_to(self.as_slices(), needle, self.len(), search::rfind_in_slice)
+    }
+
+    pub fn find_iter<'a, 'b>(&'a self, needle: &'b [T]) -> FindIter<'a, 'b, T> {
+        FindIter::new(self.as_slices(), needle, search::find_in_slice)
+    }
+}
+
+// The same searches as above, but using `memchr` on each side of the hole
+#[cfg(feature = "memchr")]
+impl<A: Allocator> HoleVec<u8, A> {
+    pub fn find_bytes(&self, needle: &[u8]) -> Option<usize> {
+        search::find_from(self.as_slices(), needle, 0, search::find_bytes)
+    }
+
+    pub fn rfind_bytes(&self, needle: &[u8]) -> Option<usize> {
+        search::rfind_to(self.as_slices(), needle, self.len(), search::rfind_bytes)
+    }
+
+    pub fn find_bytes_iter<'a, 'b>(&'a self, needle: &'b [u8]) -> FindIter<'a, 'b, u8> {
+        FindIter::new(self.as_slices(), needle, search::find_bytes)
+    }
+}
+
+impl<T: Clone, A: Allocator + Clone> Clone for HoleVec<T, A> {
+    fn clone(&self) -> Self {
+        let mut hole_vec = Self::with_capacity_in(self.len(), self.allocator().clone());
+        hole_vec.growth_policy = self.growth_policy;
+        hole_vec.marks = self.marks.clone();
+        hole_vec.extend_from_slice_before_hole(self.as_slices_before_hole());
+        hole_vec.extend_from_slice_after_hole(self.as_slices_after_hole());
+        hole_vec
+    }
+}
+
+impl<T, A: Allocator> Drop for HoleVec<T, A> {
+    fn drop(&mut self) {
+        let (before_hole, after_hole) = self.as_mut_slices();
+        unsafe {
+            ptr::drop_in_place(before_hole);
+            ptr::drop_in_place(after_hole);
+        }
+    }
+}
+
+impl<T, A: Allocator> Index<usize> for HoleVec<T, A> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        let len = self.len();
+        self.get(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T, A: Allocator> IndexMut<usize> for HoleVec<T, A> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        let len = self.len();
+        self.get_mut(index).unwrap_or_else(|| {
+            panic!(
+                "index out of bounds: the len is {} but the index is {}",
+                len, index
+            )
+        })
+    }
+}
+
+impl<T: fmt::Debug, A: Allocator> fmt::Debug for HoleVec<T, A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, B: Allocator> PartialEq<HoleVec<U, B>> for HoleVec<T, A> {
+    fn eq(&self, other: &HoleVec<U, B>) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: Eq, A: Allocator> Eq for HoleVec<T, A> {}
+
+impl<T: PartialEq<U>, U, A: Allocator> PartialEq<Vec<U>> for HoleVec<T, A> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator> PartialEq<[U]> for HoleVec<T, A> {
+    fn eq(&self, other: &[U]) -> bool {
+        self.len() == other.len() && self.iter().zip(other.iter()).all(|(a, b)| a == b)
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator> PartialEq<&[U]> for HoleVec<T, A> {
+    fn eq(&self, other: &&[U]) -> bool {
+        *self == **other
+    }
+}
+
+impl<T: PartialEq<U>, U, A: Allocator, const N: usize> PartialEq<[U; N]> for HoleVec<T, A> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        *self == other[..]
+    }
+}
+
+impl<T: PartialOrd, A: Allocator> PartialOrd for HoleVec<T, A> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, A: Allocator> Ord for HoleVec<T, A> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, A: Allocator> Hash for HoleVec<T, A> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // The slices returned by `as_slices` depend on the hole position, so
+        // the values are hashed one by one rather than with `Hash::hash_slice`.
+        state.write_usize(self.len());
+        self.iter().for_each(|value| value.hash(state));
+    }
+}
+
+impl<T, A: Allocator> Extend<T> for HoleVec<T, A> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.extend_before_hole(iter);
+    }
+}
+
+impl<'a, T: Copy + 'a, A: Allocator> Extend<&'a T> for HoleVec<T, A> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T> FromIterator<T> for HoleVec<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut hole_vec = Self::new();
+        hole_vec.extend(iter);
+        hole_vec
+    }
+}
+
+impl<T> From<Vec<T>> for HoleVec<T> {
+    fn from(vec: Vec<T>) -> Self {
+        let len = vec.len();
+        Self::from_vec(vec, len)
+    }
+}
+
+impl<T> From<VecDeque<T>> for HoleVec<T> {
+    fn from(vec: VecDeque<T>) -> Self {
+        let len = vec.len();
+        Self::from_vec_deque(vec, len)
+    }
+}
+
+impl<T> From<HoleVec<T>> for Vec<T> {
+    fn from(mut hole_vec: HoleVec<T>) -> Self {
+        // Closing the hole and the room at the front leaves the values at the
+        // start of the buffer, which can then be handed over to the `Vec` as is.
+        let len = hole_vec.len();
+        drop(mem::take(&mut hole_vec.marks));
+        hole_vec.set_hole_position(len);
+        hole_vec.relocate(0, hole_vec.capacity());
+        let buf = ManuallyDrop::new(hole_vec.into_raw_parts().0);
+        unsafe { Vec::from_raw_parts(buf.ptr(), len, buf.capacity()) }
+    }
+}
+
+impl<T> From<HoleVec<T>> for VecDeque<T> {
+    fn from(hole_vec: HoleVec<T>) -> Self {
+        Vec::from(hole_vec).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{thread_rng, Rng};
+    use std::prelude::v1::*;
+
+    #[derive(Default, Debug)]
+    struct Model<T> {
+        before_hole: Vec<T>,
+        after_hole: Vec<T>, // reversed order
+        marks: Vec<(Mark, Gravity, usize)>,
+    }
+
+    impl<T> Model<T> {
+        pub fn new() -> Self {
+            Self {
+                before_hole: Vec::new(),
+                after_hole: Vec::new(),
+                marks: Vec::new(),
+            }
+        }
+
+        fn inserted_at_hole(&mut self) {
+            let hole_position = self.before_hole.len();
+            for (_, gravity, position) in &mut self.marks {
+                if *position > hole_position
+                    || (*position == hole_position && *gravity == Gravity::Right)
+                {
+                    *position += 1;
+                }
+            }
+        }
+
+        fn removed(&mut self, index: usize) {
+            for (_, _, position) in &mut self.marks {
+                if *position > index {
+                    *position -= 1;
+                }
+            }
+        }
+
+        pub fn len(&self) -> usize {
+            self.before_hole.len() + self.after_hole.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn len_before_hole(&self) -> usize {
+            self.before_hole.len()
+        }
+
+        pub fn len_after_hole(&self) -> usize {
+            self.after_hole.len()
+        }
+
+        pub fn push_before_hole(&mut self, value: T) {
+            self.inserted_at_hole();
+            self.before_hole.push(value);
+        }
+
+        pub fn push_after_hole(&mut self, value: T) {
+            self.inserted_at_hole();
+            self.after_hole.push(value);
+        }
+
+        pub fn pop_before_hole(&mut self) -> Option<T> {
+            let value = self.before_hole.pop()?;
+            self.removed(self.before_hole.len());
+            Some(value)
+        }
+
+        pub fn pop_after_hole(&mut self) -> Option<T> {
+            let value = self.after_hole.pop()?;
+            self.removed(self.before_hole.len());
+            Some(value)
+        }
+
+        pub fn push_front(&mut self, value: T) {
+            for (_, gravity, position) in &mut self.marks {
+                if *position > 0 || *gravity == Gravity::Right {
+                    *position += 1;
+                }
+            }
+            self.before_hole.insert(0, value);
+        }
+
+        pub fn push_back(&mut self, value: T) {
+            let len = self.len();
+            for (_, gravity, position) in &mut self.marks {
+                if *position == len && *gravity == Gravity::Right {
+                    *position += 1;
+                }
+            }
+            self.after_hole.insert(0, value);
+        }
+
+        pub fn pop_front(&mut self) -> Option<T> {
+            if self.before_hole.is_empty() {
+                return self.pop_after_hole();
+            }
+            self.removed(0);
+            Some(self.before_hole.remove(0))
+        }
+
+        pub fn pop_back(&mut self) -> Option<T> {
+            if self.after_hole.is_empty() {
+                return self.pop_before_hole();
+            }
+            self.removed(self.len() - 1);
+            Some(self.after_hole.remove(0))
+        }
+
+        pub fn move_hole_right(&mut self, amount: usize) {
+            println!("moving right by {}", amount);
+            for _ in 0..amount {
+                self.before_hole.push(self.after_hole.pop().unwrap())
+            }
+        }
+
+        pub fn move_hole_left(&mut self, amount: usize) {
+            println!("moving left by {}", amount);
+            for _ in 0..amount {
+                self.after_hole.push(self.before_hole.pop().unwrap())
+            }
+        }
+
+        pub fn set_hole_position(&mut self, position: usize) {
+            assert!(position <= self.len());
+            if position > self.before_hole.len() {
+                self.move_hole_right(position - self.before_hole.len());
+            } else {
+                self.move_hole_left(self.before_hole.len() - position);
+            }
+        }
+
+        pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+            if index < self.before_hole.len() {
+                self.before_hole.get_mut(index)
+            } else {
+                let index = self.len().checked_sub(index + 1)?;
+                self.after_hole.get_mut(index)
+            }
+        }
+
+        pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+            self.before_hole().chain(self.after_hole())
+        }
+
+        pub fn before_hole(&self) -> impl DoubleEndedIterator<Item = &T> {
+            self.before_hole.iter()
+        }
+
+        pub fn after_hole(&self) -> impl DoubleEndedIterator<Item = &T> {
+            self.after_hole.iter().rev()
+        }
+    }
+
+    #[derive(Copy, Clone, Debug)]
+    enum Operation<T> {
+        PushBefore(T),
+        PushAfter(T),
+        PopBefore,
+        PopAfter,
+        MoveLeft(usize),
+        MoveRight(usize),
+        SetPosition(usize),
+        Replace(usize, T),
+        ReplaceBeforeHole(T),
+        ReplaceAfterHole(T),
+        Extend(T),
+        ExtendBefore(T, T),
+        ExtendAfter(T, T),
+        DrainBefore(usize),
+        DrainAfter(usize),
+        Insert(usize, T),
+        Remove(usize),
+        DrainRange(usize, usize),
+        Splice(usize, usize, T),
+        TryMoveLeft(usize),
+        TryMoveRight(usize),
+        TrySetPosition(usize),
+        Reserve(usize),
+        ReserveExact(usize),
+        ShrinkTo(usize),
+        CursorMove(bool),
+        CursorRemove(bool),
+        CursorInsert(usize, T),
+        AddMark(usize, bool),
+        RemoveMark(usize),
+        PushFront(T),
+        PushBack(T),
+        PopFront,
+        PopBack,
+    }
+
+    impl<T: Copy + std::fmt::Debug + Ord + Hash> Operation<T>
+    where
+        rand::distributions::Standard: rand::distributions::Distribution<T>,
+    {
+        fn rand(rng: &mut impl Rng, model: &Model<T>) -> Self {
+            match (rng.gen::<u64>() % 37, model.len() < 20) {
+                (0, _) => Operation::PushBefore(rng.gen()),
+                (1, _) => Operation::PushAfter(rng.gen()),
+                (2, _) => Operation::PopBefore,
+                (3, _) => Operation::PopAfter,
+                (4, _) => Operation::MoveLeft(rng.gen::<usize>() % (1 + model.len_before_hole())),
+                (5, _) => Operation::MoveRight(rng.gen::<usize>() % (1 + model.len_after_hole())),
+                (6, _) => Operation::SetPosition(rng.gen::<usize>() % (1 + model.len())),
+                (7, _) if !model.is_empty() => {
+                    Operation::Replace(rng.gen::<usize>() % model.len(), rng.gen())
+                }
+                (8, _) => Operation::ReplaceBeforeHole(rng.gen()),
+                (9, _) => Operation::ReplaceAfterHole(rng.gen()),
+                (10, true) => Operation::Extend(rng.gen()),
+                (11, true) => Operation::ExtendBefore(rng.gen(), rng.gen()),
+                (12, true) => Operation::ExtendAfter(rng.gen(), rng.gen()),
+                (13, _) => {
+                    Operation::DrainBefore(rng.gen::<usize>() % (1 + model.len_before_hole()))
+                }
+                (14, _) => Operation::DrainAfter(rng.gen::<usize>() % (1 + model.len_after_hole())),
+                (15, true) => Operation::Insert(rng.gen::<usize>() % (1 + model.len()), rng.gen()),
+                (16, _) if !model.is_empty() => {
+                    Operation::Remove(rng.gen::<usize>() % model.len())
+                }
+                (17, _) => {
+                    let start = rng.gen::<usize>() % (1 + model.len());
+                    let end = start + rng.gen::<usize>() % (1 + model.len() - start);
+                    Operation::DrainRange(start, end)
+                }
+                (18, true) => {
+                    let start = rng.gen::<usize>() % (1 + model.len());
+                    let end = start + rng.gen::<usize>() % (1 + model.len() - start);
+                    Operation::Splice(start, end, rng.gen())
+                }
+                (19, _) => {
+                    Operation::TryMoveLeft(rng.gen::<usize>() % (3 + model.len_before_hole()))
+                }
+                (20, _) => {
+                    Operation::TryMoveRight(rng.gen::<usize>() % (3 + model.len_after_hole()))
+                }
+                (21, _) => Operation::TrySetPosition(rng.gen::<usize>() % (3 + model.len())),
+                (22, _) => Operation::Reserve(rng.gen::<usize>() % 20),
+                (23, _) => Operation::ReserveExact(rng.gen::<usize>() % 20),
+                (24, _) => Operation::ShrinkTo(rng.gen::<usize>() % 40),
+                (28, _) => {
+                    Operation::AddMark(rng.gen::<usize>() % (1 + model.len()), rng.gen::<bool>())
+                }
+                (29, _) => Operation::RemoveMark(rng.gen::<usize>() % (1 + model.marks.len())),
+                (30, true) => Operation::PushFront(rng.gen()),
+                (31, true) => Operation::PushBack(rng.gen()),
+                (32, _) => Operation::PopFront,
+                (33, _) => Operation::PopBack,
+                (25, _) => Operation::CursorMove(rng.gen::<bool>()),
+                (26, _) => Operation::CursorRemove(rng.gen::<bool>()),
+                (27, true) => {
+                    Operation::CursorInsert(rng.gen::<usize>() % (1 + model.len()), rng.gen())
+                }
+                (n, false) => {
+                    if n % 2 == 0 {
+                        Operation::PopBefore
+                    } else {
+                        Operation::PopAfter
+                    }
+                }
+                (n, _) => {
+                    if n % 2 == 0 {
+                        Operation::PushBefore(rng.gen())
+                    } else {
+                        Operation::PushAfter(rng.gen())
+                    }
+                }
+            }
+        }
+
+        fn apply(self, hole: &mut HoleVec<T>, model: &mut Model<T>) {
+            match self {
+                Operation::PushBefore(value) => {
+                    hole.push_before_hole(value);
+                    model.push_before_hole(value);
+                }
+                Operation::PushAfter(value) => {
+                    hole.push_after_hole(value);
+                    model.push_after_hole(value);
+                }
+                Operation::PopBefore => {
+                    assert_eq!(hole.pop_before_hole(), model.pop_before_hole());
+                }
+                Operation::PushFront(value) => {
+                    hole.push_front(value);
+                    model.push_front(value);
+                }
+                Operation::PushBack(value) => {
+                    hole.push_back(value);
+                    model.push_back(value);
+                }
+                Operation::PopFront => {
+                    assert_eq!(hole.pop_front(), model.pop_front());
+                }
+                Operation::PopBack => {
+                    assert_eq!(hole.pop_back(), model.pop_back());
+                }
+                Operation::PopAfter => {
+                    assert_eq!(hole.pop_after_hole(), model.pop_after_hole());
+                }
+                Operation::MoveLeft(amount) => {
+                    hole.move_hole_left(amount);
+                    model.move_hole_left(amount);
+                }
+                Operation::MoveRight(amount) => {
+                    hole.move_hole_right(amount);
+                    model.move_hole_right(amount);
+                }
+                Operation::SetPosition(pos) => {
+                    hole.set_hole_position(pos);
+                    model.set_hole_position(pos);
+                }
+                Operation::CursorMove(forward) => {
+                    let mut cursor = hole.cursor_mut();
+                    if forward {
+                        assert_eq!(cursor.move_next(), model.len_after_hole() > 0);
+                        model.move_hole_right(model.len_after_hole().min(1));
+                    } else {
+                        assert_eq!(cursor.move_prev(), model.len_before_hole() > 0);
+                        model.move_hole_left(model.len_before_hole().min(1));
+                    }
+                }
+                Operation::CursorRemove(forward) => {
+                    let mut cursor = hole.cursor_mut();
+                    if forward {
+                        assert_eq!(
+                            cursor.peek_next().copied(),
+                            model.after_hole().next().copied()
+                        );
+                        assert_eq!(cursor.remove_next(), model.pop_after_hole());
+                    } else {
+                        assert_eq!(
+                            cursor.peek_prev().copied(),
+                            model.before_hole().last().copied()
+                        );
+                        assert_eq!(cursor.remove_prev(), model.pop_before_hole());
+                    }
+                }
+                Operation::CursorInsert(position, value) => {
+                    let mut cursor = hole.cursor_mut();
+                    cursor.seek(position);
+                    cursor.insert(value);
+                    assert_eq!(cursor.position(), position + 1);
+                    model.set_hole_position(position);
+                    model.push_before_hole(value);
+                }
+                Operation::AddMark(position, left) => {
+                    let gravity = if left { Gravity::Left } else { Gravity::Right };
+                    let mark = hole.add_mark(position, gravity);
+                    assert_eq!(hole.mark_gravity(mark), Some(gravity));
+                    model.marks.push((mark, gravity, position));
+                }
+                Operation::RemoveMark(index) => {
+                    if index < model.marks.len() {
+                        let (mark, _, position) = model.marks.swap_remove(index);
+                        assert_eq!(hole.remove_mark(mark), Some(position));
+                        assert_eq!(hole.mark_position(mark), None);
+                        assert_eq!(hole.remove_mark(mark), None);
+                    }
+                }
+                Operation::Reserve(additional) => {
+                    hole.reserve(additional);
+                    assert!(hole.gap_len() >= additional);
+                }
+                Operation::ReserveExact(additional) => {
+                    let capacity = hole.capacity();
+                    hole.reserve_exact(additional);
+                    if additional > capacity - model.len() {
+                        assert_eq!(hole.capacity(), model.len() + additional);
+                    } else {
+                        assert_eq!(hole.capacity(), capacity);
+                    }
+                }
+                Operation::ShrinkTo(min_capacity) => {
+                    let capacity = hole.capacity();
+                    hole.shrink_to(min_capacity);
+                    if std::mem::size_of::<T>() == 0 {
+                        assert_eq!(hole.capacity(), usize::MAX);
+                    } else {
+                        let min_capacity = min_capacity.max(model.len());
+                        assert_eq!(hole.capacity(), capacity.min(min_capacity));
+                    }
+                }
+                Operation::TryMoveLeft(amount) => {
+                    let available = model.len_before_hole();
+                    if amount <= available {
+                        assert_eq!(hole.try_move_hole_left(amount), Ok(()));
+                        model.move_hole_left(amount);
+                    } else {
+                        let err = HoleVecError::OutOfBounds {
+                            requested: amount,
+                            available,
+                        };
+                        assert_eq!(hole.try_move_hole_left(amount), Err(err));
+                    }
+                }
+                Operation::TryMoveRight(amount) => {
+                    let available = model.len_after_hole();
+                    if amount <= available {
+                        assert_eq!(hole.try_move_hole_right(amount), Ok(()));
+                        model.move_hole_right(amount);
+                    } else {
+                        let err = HoleVecError::OutOfBounds {
+                            requested: amount,
+                            available,
+                        };
+                        assert_eq!(hole.try_move_hole_right(amount), Err(err));
+                    }
+                }
+                Operation::TrySetPosition(pos) => {
+                    let available = model.len();
+                    if pos <= available {
+                        assert_eq!(hole.try_set_hole_position(pos), Ok(()));
+                        model.set_hole_position(pos);
+                    } else {
+                        let err = HoleVecError::OutOfBounds {
+                            requested: pos,
+                            available,
+                        };
+                        assert_eq!(hole.try_set_hole_position(pos), Err(err));
+                    }
+                }
+                Operation::Replace(index, value) => {
+                    hole[index] = value;
+                    *model.get_mut(index).unwrap() = value;
+                }
+                Operation::ReplaceBeforeHole(value) => {
+                    if let Some(last) = hole.as_mut_slices_before_hole().last_mut() {
+                        *last = value;
+                    }
+                    if let Some(last) = model.before_hole.last_mut() {
+                        *last = value;
+                    }
+                }
+                Operation::ExtendBefore(a, b) => {
+                    if a < b {
+                        hole.extend_from_slice_before_hole(&[a, b]);
+                    } else {
+                        hole.extend_before_hole(vec![a, b]);
+                    }
+                    model.push_before_hole(a);
+                    model.push_before_hole(b);
+                }
+                Operation::ExtendAfter(a, b) => {
+                    if a < b {
+                        hole.extend_from_slice_after_hole(&[a, b]);
+                    } else if model.len() % 2 == 0 {
+                        hole.extend_after_hole(vec![a, b]);
+                    } else {
+                        hole.extend_after_hole(vec![a, b].into_iter().filter(|_| true));
+                    }
+                    model.push_after_hole(b);
+                    model.push_after_hole(a);
+                }
+                Operation::DrainBefore(amount) => {
+                    let mut drain = hole.drain_before_hole(amount);
+                    assert_eq!(drain.len(), amount);
+                    let mut drained = (0..amount)
+                        .map(|_| model.pop_before_hole().unwrap())
+                        .collect::<Vec<T>>();
+                    drained.reverse();
+                    if amount % 2 == 0 {
+                        assert!(drain.eq(drained));
+                    } else {
+                        // Dropping a partially consumed iterator still removes everything
+                        assert_eq!(drain.next(), drained.first().copied());
+                    }
+                }
+                Operation::DrainAfter(amount) => {
+                    let drain = hole.drain_after_hole(amount);
+                    assert_eq!(drain.len(), amount);
+                    let drained = (0..amount).map(|_| model.pop_after_hole().unwrap());
+                    assert!(drain.eq(drained));
+                }
+                Operation::Insert(index, value) => {
+                    hole.insert(index, value);
+                    model.set_hole_position(index);
+                    model.push_before_hole(value);
+                }
+                Operation::Remove(index) => {
+                    model.set_hole_position(index);
+                    assert_eq!(Some(hole.remove(index)), model.pop_after_hole());
+                }
+                Operation::DrainRange(start, end) => {
+                    let drained = hole.drain(start..end).collect::<Vec<T>>();
+                    model.set_hole_position(start);
+                    let expected = (start..end).map(|_| model.pop_after_hole().unwrap());
+                    assert!(drained.into_iter().eq(expected));
+                }
+                Operation::Splice(start, end, value) => {
+                    let drained = if start < end {
+                        hole.splice(start..=end - 1, vec![value; 3])
+                            .collect::<Vec<T>>()
+                    } else {
+                        hole.splice(start..end, vec![value; 3]).collect::<Vec<T>>()
+                    };
+                    model.set_hole_position(start);
+                    let expected = (start..end).map(|_| model.pop_after_hole().unwrap());
+                    assert!(drained.into_iter().eq(expected));
+                    for _ in 0..3 {
+                        model.push_before_hole(value);
+                    }
+                }
+                Operation::Extend(value) => {
+                    hole.extend(&[value, value]);
+                    model.push_before_hole(value);
+                    model.push_before_hole(value);
+                }
+                Operation::ReplaceAfterHole(value) => {
+                    if let Some(first) = hole.as_mut_slices_after_hole().first_mut() {
+                        *first = value;
+                    }
+                    if let Some(first) = model.after_hole.last_mut() {
+                        *first = value;
+                    }
+                }
+            }
+
+            assert_eq!(hole.len(), model.len());
+            assert_eq!(hole.len_before_hole(), model.len_before_hole());
+            assert_eq!(hole.len_after_hole(), model.len_after_hole());
+            assert_eq!(hole.is_empty(), model.is_empty());
+            assert!(hole.gap_len() <= hole.capacity() - model.len());
+
+            for &(mark, _, position) in &model.marks {
+                assert_eq!(hole.mark_position(mark), Some(position));
+            }
+
+            let mut cursor = hole.cursor();
+            assert_eq!(cursor.position(), model.len_before_hole());
+            assert_eq!(cursor.peek_next(), model.after_hole().next());
+            assert_eq!(cursor.peek_prev(), model.before_hole().last());
+            cursor.seek(0);
+            for value in model.iter() {
+                assert_eq!(cursor.peek_next(), Some(value));
+                assert!(cursor.move_next());
+                assert_eq!(cursor.peek_prev(), Some(value));
+            }
+            assert!(!cursor.move_next());
+            assert_eq!(cursor.position(), model.len());
+
+            for (index, value) in model.iter().enumerate() {
+                assert_eq!(hole.get(index), Some(value));
+                assert_eq!(&hole[index], value);
+            }
+            assert_eq!(hole.get(model.len()), None);
+            assert_eq!(hole.first(), model.iter().next());
+            assert_eq!(hole.last(), model.iter().last());
+
+            assert!(hole.as_slices_before_hole().iter().eq(model.before_hole()));
+            assert!(hole.as_slices_after_hole().iter().eq(model.after_hole()));
+
+            let (a0, a1) = hole.as_slices();
+            assert!(a0.iter().chain(a1.iter()).eq(model.iter()));
+
+            assert!(hole.iter().eq(model.iter()));
+            assert!(hole.iter().rev().eq(model.iter().rev()));
+            assert_eq!(hole.iter().len(), model.len());
+            assert!(hole.iter_before_hole().eq(model.before_hole()));
+            assert!(hole.iter_after_hole().eq(model.after_hole()));
+            assert_eq!(hole.iter_before_hole().len(), model.len_before_hole());
+            assert_eq!(hole.iter_after_hole().len(), model.len_after_hole());
+
+            let values = model.iter().copied().collect::<Vec<T>>();
+            assert_eq!(*hole, values);
+            assert_eq!(format!("{:?}", hole), format!("{:?}", values));
+            assert_eq!(Vec::from(hole.clone()), values);
+            assert_eq!(VecDeque::from(hole.clone()), values);
+            assert_eq!(hole.clone().into_vec(), values);
+
+            // A `HoleVec` without spare capacity is turned into a boxed slice
+            // in place
+            let mut exact = hole.clone();
+            exact.shrink_to_fit();
+            let ptr = exact.buf.ptr() as *const T;
+            let boxed = exact.into_boxed_slice();
+            assert_eq!(*boxed, values[..]);
+            assert_eq!(boxed.as_ptr(), ptr);
+
+            let mut contiguous = hole.clone();
+            contiguous.reserve_exact(values.len() % 3);
+            let ptr = contiguous.buf.ptr() as *const T;
+            let capacity = contiguous.capacity();
+            assert_eq!(contiguous.make_contiguous(), &values[..]);
+            assert_eq!(contiguous.len_before_hole(), model.len_before_hole());
+            assert!(contiguous.iter_before_hole().eq(model.before_hole()));
+            assert_eq!(contiguous.capacity(), capacity);
+            assert_eq!(contiguous, values);
+            let vec = contiguous.into_vec();
+            assert_eq!(vec.as_ptr(), ptr);
+            assert_eq!(vec.capacity(), capacity);
+
+            let (start, end) = (values.len() / 3, values.len() - values.len() / 4);
+            let range = hole.range(start..end);
+            assert_eq!(range, values[start..end]);
+            assert_eq!(range.to_vec(), &values[start..end]);
+            for index in 0..=range.len() {
+                assert_eq!(range.get(index), values[start..end].get(index));
+            }
+            let middle = start + range.len() / 2;
+            assert_eq!(range.range(..middle - start), values[start..middle]);
+            assert_eq!(range.range(middle - start..), values[middle..end]);
+            assert_eq!(hole.range(..), values[..]);
+
+            let other = HoleVec::from_vec(values.clone(), model.len_before_hole());
+            assert!(other.iter_before_hole().eq(model.before_hole()));
+            assert!(other.iter_after_hole().eq(model.after_hole()));
+
+            let other = values.iter().copied().collect::<HoleVec<T>>();
+            assert_eq!(*hole, other);
+            assert_eq!((*hole).cmp(&other), Ordering::Equal);
+            assert_eq!(hash(&*hole), hash(&other));
+            if let Some(last) = values.last() {
+                let mut shorter = HoleVec::from(values[..values.len() - 1].to_vec());
+                assert!(shorter < *hole);
+                shorter.push_before_hole(*last);
+                assert_eq!(shorter, *hole);
+            }
+
+            let mut copy = hole.clone();
+
+            assert!(copy
+                .as_mut_slices_before_hole()
+                .iter()
+                .eq(model.before_hole()));
+            assert!(copy
+                .as_mut_slices_after_hole()
+                .iter()
+                .eq(model.after_hole()));
+
+            let (a0, a1) = copy.as_mut_slices();
+            assert!(a0.iter().chain(a1.iter()).eq(model.iter()));
+
+            let mut range = copy.range_mut(start..end);
+            assert_eq!(range, values[start..end]);
+            for (value, new_value) in range.iter_mut().zip(values[start..end].iter().rev()) {
+                *value = *new_value;
+            }
+            let mut reversed = values.clone();
+            reversed[start..end].reverse();
+            assert_eq!(copy, reversed);
+            copy.range_mut(start..end)
+                .into_iter()
+                .zip(&values[start..end])
+                .for_each(|(value, new_value)| *value = *new_value);
+
+            assert_eq!(copy.iter_mut().len(), model.len());
+            assert!(copy.iter_mut().map(|value| &*value).eq(model.iter()));
+            assert!(copy.clone().into_iter().eq(model.iter().copied()));
+            assert!(copy.into_iter().rev().eq(model.iter().rev().copied()));
+        }
+    }
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn run_test<T: std::fmt::Debug + Ord + Hash + Copy>()
+    where
+        rand::distributions::Standard: rand::distributions::Distribution<T>,
+    {
+        let mut rng = thread_rng();
+        for _ in 0..1000 {
+            let mut model = Model::<T>::new();
+            let mut hole_vec = HoleVec::<T>::new();
+            hole_vec.set_growth_policy(match rng.gen::<u8>() % 3 {
+                0 => GrowthPolicy::Doubling,
+                1 => GrowthPolicy::Fixed(rng.gen::<usize>() % 4),
+                _ => GrowthPolicy::MaxGap(rng.gen::<usize>() % 4),
+            });
+
+            for _ in 0..1000 {
+                println!("{:?}", model);
+                println!("{:?}", hole_vec.as_slices());
+                let operation = Operation::rand(&mut rng, &model);
+                println!("{:?}", operation);
+                operation.apply(&mut hole_vec, &mut model);
+            }
+        }
+    }
+
+    #[test]
+    fn it_works() {
+        run_test::<u8>();
+        run_test::<u32>();
+        run_test::<()>();
+    }
+
+    fn random_hole_vec(rng: &mut impl Rng) -> (HoleVec<String>, Vec<String>) {
+        let len = rng.gen::<usize>() % 20;
+        let values = (0..len)
+            .map(|_| rng.gen::<u8>().to_string())
+            .collect::<Vec<_>>();
+        let mut hole_vec = HoleVec::from_vec(values.clone(), rng.gen::<usize>() % (len + 1));
+        hole_vec.reserve(rng.gen::<usize>() % 20);
+        (hole_vec, values)
+    }
+
+    #[test]
+    fn front_and_back_marks() {
+        for gravity in [Gravity::Left, Gravity::Right] {
+            let right = (gravity == Gravity::Right) as usize;
+            for len in [0, 1, 4] {
+                for hole_position in 0..=len {
+                    let new = || {
+                        let mut hole_vec = HoleVec::from_vec((0..len).collect(), hole_position);
+                        let start = hole_vec.add_mark(0, gravity);
+                        let end = hole_vec.add_mark(len, gravity);
+                        (hole_vec, start, end)
+                    };
+                    let positions = |(hole_vec, start, end): (HoleVec<usize>, Mark, Mark)| {
+                        (
+                            hole_vec.mark_position(start).unwrap(),
+                            hole_vec.mark_position(end).unwrap(),
+                            hole_vec.len_before_hole(),
+                        )
+                    };
+
+                    let mut state = new();
+                    state.0.push_front(len);
+                    let start = right;
+                    let end = if len == 0 { right } else { len + 1 };
+                    assert_eq!(positions(state), (start, end, hole_position + 1));
+
+                    let mut state = new();
+                    state.0.push_back(len);
+                    let start = if len == 0 { right } else { 0 };
+                    assert_eq!(positions(state), (start, len + right, hole_position));
+
+                    let mut state = new();
+                    assert_eq!(state.0.pop_front(), (len > 0).then_some(0));
+                    let hole = hole_position.saturating_sub(1);
+                    assert_eq!(positions(state), (0, len.saturating_sub(1), hole));
+
+                    let mut state = new();
+                    assert_eq!(state.0.pop_back(), len.checked_sub(1));
+                    let end = len.saturating_sub(1);
+                    assert_eq!(positions(state), (0, end, hole_position.min(end)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn front_and_back_leave_values_in_place() {
+        // The values on either side only move when the room at the ends runs
+        // out, which happens a logarithmic number of times
+        let mut hole_vec = HoleVec::from_vec((0..1000).collect(), 500);
+        let mut moves = 0;
+        for value in 0..10000 {
+            // The values next to the hole are only moved along with the rest
+            let slices = |hole_vec: &HoleVec<usize>| {
+                let (before_hole, after_hole) = hole_vec.as_slices();
+                (before_hole.last().unwrap() as *const usize, after_hole.as_ptr())
+            };
+            let old = slices(&hole_vec);
+            hole_vec.push_back(value);
+            hole_vec.push_front(value);
+            moves += (slices(&hole_vec) != old) as usize;
+            // The room at the ends is not part of the gap
+            let (before_hole, after_hole) = hole_vec.as_slices();
+            let hole = unsafe { after_hole.as_ptr().offset_from(before_hole.as_ptr_range().end) };
+            assert_eq!(hole_vec.gap_len(), hole as usize);
+        }
+        assert!(moves <= 10);
+        assert_eq!(hole_vec.len_before_hole(), 10500);
+        let values = (0..10000).rev().chain(0..1000).chain(0..10000);
+        assert!(hole_vec.iter().copied().eq(values.clone()));
+        assert!(hole_vec.into_vec().into_iter().eq(values));
+    }
+
+    #[test]
+    #[should_panic(expected = "removal index (is 3) should be < len (is 3)")]
+    fn remove_out_of_bounds() {
+        HoleVec::from_vec(vec![0, 1, 2], 1).remove(3);
+    }
+
+    // An iterator that claims to have exactly `len` values left, whatever it
+    // actually yields
+    struct WrongSizeHint<I>(I, usize);
+
+    impl<I: Iterator> Iterator for WrongSizeHint<I> {
+        type Item = I::Item;
+
+        fn next(&mut self) -> Option<I::Item> {
+            self.0.next()
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.1, Some(self.1))
+        }
+    }
+
+    #[test]
+    fn extend_after_hole_with_wrong_size_hint() {
+        for hint in 0..6 {
+            let mut hole_vec = HoleVec::from_vec(vec![0, 1, 5, 6], 2);
+            let mark = hole_vec.add_mark(2, Gravity::Right);
+            hole_vec.extend_after_hole(WrongSizeHint(2..5, hint));
+            assert_eq!(hole_vec, [0, 1, 2, 3, 4, 5, 6]);
+            assert_eq!(hole_vec.len_before_hole(), 2);
+            assert_eq!(hole_vec.mark_position(mark), Some(5));
+        }
+    }
+
+    #[test]
+    fn split_and_append() {
+        let mut rng = thread_rng();
+        for _ in 0..10000 {
+            let (mut hole_vec, values) = random_hole_vec(&mut rng);
+            let hole_position = hole_vec.len_before_hole();
+            let mark_position = rng.gen::<usize>() % (values.len() + 1);
+            let mark = hole_vec.add_mark(mark_position, Gravity::Left);
+            let tail = hole_vec.split_at_hole();
+            assert_eq!(hole_vec, values[..hole_position]);
+            assert_eq!(hole_vec.len_after_hole(), 0);
+            assert_eq!(tail, values[hole_position..]);
+            assert_eq!(tail.len_before_hole(), 0);
+            let position = mark_position.min(hole_position);
+            assert_eq!(hole_vec.mark_position(mark), Some(position));
+
+            let (mut hole_vec, values) = random_hole_vec(&mut rng);
+            let at = rng.gen::<usize>() % (values.len() + 1);
+            let tail = hole_vec.split_off(at);
+            assert_eq!(hole_vec, values[..at]);
+            assert_eq!(hole_vec.len_before_hole(), at);
+            assert_eq!(tail, values[at..]);
+            assert_eq!(tail.len_before_hole(), 0);
+
+            let (mut a, mut values) = random_hole_vec(&mut rng);
+            let (mut b, b_values) = random_hole_vec(&mut rng);
+            let hole_position = a.len_before_hole();
+            let len = values.len();
+            let marks = [
+                (a.add_mark(len, Gravity::Left), len),
+                (a.add_mark(len, Gravity::Right), len + b_values.len()),
+                (a.add_mark(len / 2, Gravity::Left), len / 2),
+            ];
+            let b_mark = b.add_mark(b_values.len(), Gravity::Left);
+            a.append(&mut b);
+            values.extend(b_values);
+            assert_eq!(a, values);
+            assert_eq!(a.len_before_hole(), hole_position);
+            for (mark, position) in marks {
+                assert_eq!(a.mark_position(mark), Some(position));
+            }
+            assert!(b.is_empty());
+            assert_eq!(b.mark_position(b_mark), Some(0));
+            b.push_before_hole("b".to_string());
+            assert_eq!(b, ["b"]);
+        }
+    }
+
+    fn naive_find<T: PartialEq>(values: &[T], needle: &[T]) -> Vec<usize> {
+        let mut positions = Vec::new();
+        let mut start = 0;
+        while start + needle.len() <= values.len() {
+            if values[start..start + needle.len()] == *needle {
+                positions.push(start);
+                start += needle.len().max(1);
+            } else {
+                start += 1;
+            }
+        }
+        positions
+    }
+
+    #[test]
+    fn search() {
+        let mut rng = thread_rng();
+        for _ in 0..10000 {
+            let len = rng.gen::<usize>() % 20;
+            let values = (0..len).map(|_| rng.gen::<u8>() % 3).collect::<Vec<_>>();
+            let hole_vec = HoleVec::from_vec(values.clone(), rng.gen::<usize>() % (len + 1));
+            let needle = (0..rng.gen::<usize>() % 5)
+                .map(|_| rng.gen::<u8>() % 3)
+                .collect::<Vec<_>>();
+
+            let expected = naive_find(&values, &needle);
+            assert!(hole_vec.find_iter(&needle).eq(expected.iter().copied()));
+            #[cfg(feature = "memchr")]
+            assert!(hole_vec
+                .find_bytes_iter(&needle)
+                .eq(expected.iter().copied()));
+            assert_eq!(hole_vec.find(&needle), expected.first().copied());
+            #[cfg(feature = "memchr")]
+            assert_eq!(hole_vec.find_bytes(&needle), expected.first().copied());
+
+            let last = (0..=len)
+                .rev()
+                .find(|&start| values[start..].starts_with(&needle));
+            assert_eq!(hole_vec.rfind(&needle), last);
+            #[cfg(feature = "memchr")]
+            assert_eq!(hole_vec.rfind_bytes(&needle), last);
+
+            let value = rng.gen::<u8>() % 3;
+            assert_eq!(hole_vec.contains(&value), values.contains(&value));
+            assert_eq!(
+                hole_vec.position(|&v| v == value),
+                values.iter().position(|&v| v == value)
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn io() {
+        use std::io::{BufRead, IoSlice, IoSliceMut, Read, Seek, SeekFrom, Write};
+
+        let mut hole_vec = HoleVec::from_vec(b"hello world".to_vec(), 5);
+        hole_vec.write_all(b",").unwrap();
+        let bufs = [
+            IoSlice::new(b" big"),
+            IoSlice::new(b""),
+            IoSlice::new(b" wide"),
+        ];
+        assert_eq!(hole_vec.write_vectored(&bufs).unwrap(), 9);
+        assert_eq!(hole_vec, *b"hello, big wide world");
+        assert_eq!(hole_vec.len_before_hole(), 15);
+
+        let mut written = Vec::new();
+        let slices = hole_vec.as_io_slices();
+        assert_eq!(written.write_vectored(&slices).unwrap(), hole_vec.len());
+        assert_eq!(hole_vec, written);
+
+        let mut word = [0; 3];
+        hole_vec.read_exact(&mut word).unwrap();
+        assert_eq!(&word, b" wo");
+        assert_eq!(hole_vec.fill_buf().unwrap(), b"rld");
+        hole_vec.consume(1);
+        assert_eq!(hole_vec.stream_position().unwrap(), 19);
+
+        assert_eq!(hole_vec.seek(SeekFrom::Start(2)).unwrap(), 2);
+        let (mut a, mut b) = ([0; 3], [0; 2]);
+        let mut bufs = [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b)];
+        assert_eq!(hole_vec.read_vectored(&mut bufs).unwrap(), 5);
+        assert_eq!((&a, &b), (b"llo", b", "));
+
+        assert_eq!(hole_vec.seek(SeekFrom::Current(-7)).unwrap(), 0);
+        assert_eq!(hole_vec.seek(SeekFrom::End(-5)).unwrap(), 16);
+        assert!(hole_vec.seek(SeekFrom::Current(-17)).is_err());
+        assert!(hole_vec.seek(SeekFrom::End(1)).is_err());
+        assert!(hole_vec.seek(SeekFrom::Start(u64::MAX)).is_err());
+        assert_eq!(hole_vec.len_before_hole(), 16);
+
+        let mut rest = String::new();
+        hole_vec.read_to_string(&mut rest).unwrap();
+        assert_eq!(rest, "world");
+        assert_eq!(hole_vec.read(&mut word).unwrap(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde() {
+        use serde_test::{assert_de_tokens_error, assert_tokens, Token};
+
+        let hole_vec = HoleVec::from_vec(vec![1u32, 2, 3], 1);
+        assert_tokens(
+            &hole_vec,
+            &[
+                Token::Struct {
+                    name: "HoleVec",
+                    len: 2,
+                },
+                Token::Str("values"),
+                Token::Seq { len: Some(3) },
+                Token::U32(1),
+                Token::U32(2),
+                Token::U32(3),
+                Token::SeqEnd,
+                Token::Str("hole_position"),
+                Token::U64(1),
+                Token::StructEnd,
+            ],
+        );
+        assert_de_tokens_error::<HoleVec<u32>>(
+            &[
+                Token::Seq { len: Some(2) },
+                Token::Seq { len: Some(0) },
+                Token::SeqEnd,
+                Token::U64(1),
+                Token::SeqEnd,
+            ],
+            "invalid value: integer `1`, expected a hole position within the values",
+        );
+
+        let bytes = bincode::serialize(&hole_vec).unwrap();
+        let other: HoleVec<u32> = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(other, hole_vec);
+        assert_eq!(other.len_before_hole(), 1);
+    }
+
+    #[test]
+    fn custom_allocator() {
+        use allocator_api2::alloc::{AllocError, Layout};
+        use core::cell::Cell;
+        use core::ptr::NonNull;
+
+        // Keeps track of the amount of bytes it has handed out
+        #[derive(Clone, Copy)]
+        struct Counting<'a>(&'a Cell<usize>);
+
+        unsafe impl Allocator for Counting<'_> {
+            fn allocate(&self, layout: Layout) -> Result<NonNull<[u8]>, AllocError> {
+                self.0.set(self.0.get() + layout.size());
+                Global.allocate(layout)
+            }
+
+            unsafe fn deallocate(&self, ptr: NonNull<u8>, layout: Layout) {
+                self.0.set(self.0.get() - layout.size());
+                Global.deallocate(ptr, layout)
+            }
+        }
+
+        let allocated = Cell::new(0);
+        let mut hole_vec = HoleVec::new_in(Counting(&allocated));
+        hole_vec.extend(0u32..10);
+        hole_vec.set_hole_position(4);
+        assert_eq!(allocated.get(), hole_vec.capacity() * 4);
+
+        hole_vec.shrink_to_fit();
+        assert_eq!(allocated.get(), 40);
+        let clone = hole_vec.clone();
+        assert_eq!(clone, hole_vec);
+        assert_eq!(allocated.get(), 80);
+
+        let mut iter = clone.into_iter();
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(9));
+        drop(iter);
+        assert_eq!(allocated.get(), 40);
+
+        // Appending a larger `HoleVec` copies its values over rather than
+        // taking over its buffer, so that each `HoleVec` keeps its allocator
+        let other_allocated = Cell::new(0);
+        let mut other = HoleVec::new_in(Counting(&other_allocated));
+        other.extend(10u32..100);
+        other.set_hole_position(1);
+        let other_capacity = other.capacity();
+        hole_vec.append(&mut other);
+        assert_eq!(hole_vec, (0..100).collect::<Vec<_>>());
+        assert_eq!(allocated.get(), hole_vec.capacity() * 4);
+        assert_eq!(other.capacity(), other_capacity);
+        assert_eq!(other_allocated.get(), other_capacity * 4);
+        drop(other);
+        assert_eq!(other_allocated.get(), 0);
+
+        // Appending a smaller `HoleVec` only moves the values after the hole
+        // as far as the room at the end falls short
+        hole_vec.reserve_exact(10);
+        hole_vec.set_hole_position(90);
+        assert_eq!(hole_vec.pop_back(), Some(99));
+        let before_hole = hole_vec.as_slices_before_hole().as_ptr();
+        let after_hole = hole_vec.as_slices_after_hole().as_ptr();
+        let mut other = HoleVec::new_in(Counting(&other_allocated));
+        other.extend(99u32..103);
+        hole_vec.append(&mut other);
+        assert_eq!(hole_vec, (0..103).collect::<Vec<_>>());
+        assert_eq!(hole_vec.as_slices_before_hole().as_ptr(), before_hole);
+        assert_eq!(hole_vec.as_slices_after_hole().as_ptr(), after_hole.wrapping_sub(3));
+        assert_eq!(allocated.get(), hole_vec.capacity() * 4);
+        assert_eq!(other_allocated.get(), other.capacity() * 4);
+        drop(other);
+        assert_eq!(other_allocated.get(), 0);
+        drop(hole_vec);
+        assert_eq!(allocated.get(), 0);
+
+        // With `Global` the larger buffer is taken over instead
+        let mut hole_vec = HoleVec::from_vec(vec![0], 1);
+        let mut values = Vec::with_capacity(200);
+        values.extend(1..100);
+        let mut other = HoleVec::from_vec(values, 1);
+        let ptr = other.as_slices_after_hole().as_ptr();
+        hole_vec.append(&mut other);
+        assert_eq!(hole_vec, (0..100).collect::<Vec<_>>());
+        assert_eq!(hole_vec.as_slices_after_hole().as_ptr(), ptr.wrapping_sub(1));
+    }
+
+    #[test]
+    fn growth_policies() {
+        let mut hole_vec = HoleVec::<u32>::new();
+        hole_vec.extend(0..5);
+        assert_eq!(hole_vec.capacity(), 5);
+        hole_vec.push_before_hole(5);
+        assert_eq!(hole_vec.capacity(), 10);
+
+        hole_vec.set_growth_policy(GrowthPolicy::Fixed(3));
+        hole_vec.extend(6..11);
+        assert_eq!(hole_vec.capacity(), 13);
+        hole_vec.extend(11..20);
+        assert_eq!(hole_vec.capacity(), 20);
+
+        hole_vec.set_growth_policy(GrowthPolicy::MaxGap(2));
+        hole_vec.move_hole_left(10);
+        hole_vec.push_after_hole(20);
+        assert_eq!(hole_vec.capacity(), 22);
+        assert_eq!(hole_vec.gap_len(), 1);
+
+        hole_vec.shrink_to_fit();
+        assert_eq!(hole_vec.capacity(), 21);
+        assert!(hole_vec
+            .iter()
+            .copied()
+            .eq((0..10).chain(Some(20)).chain(10..20)));
+    }
+
+    #[test]
+    fn reports_allocation_failures() {
+        let mut hole_vec = HoleVec::<u32>::new();
+        hole_vec.push_before_hole(1);
+        assert_eq!(
+            hole_vec.try_reserve(usize::MAX),
+            Err(HoleVecError::CapacityOverflow)
+        );
+        assert_eq!(
+            hole_vec.try_reserve(usize::MAX / 4),
+            Err(HoleVecError::CapacityOverflow)
+        );
+        assert!(matches!(
+            hole_vec.try_reserve(isize::MAX as usize / 8),
+            Err(HoleVecError::AllocError { .. })
+        ));
+        assert_eq!(hole_vec.try_reserve(100), Ok(()));
+        assert_eq!(hole_vec.as_slices(), (&[1][..], &[][..]));
+
+        assert!(HoleVec::<u8>::try_with_capacity(100).is_ok());
+        assert!(HoleVec::<u8>::try_with_capacity(usize::MAX).is_err());
+        assert!(HoleVec::<()>::try_with_capacity(usize::MAX).is_ok());
+    }
+
+    #[test]
+    fn drops_values() {
+        let value = std::rc::Rc::new(());
+        let mut hole_vec = HoleVec::new();
+        for _ in 0..10 {
+            hole_vec.push_before_hole(value.clone());
+            hole_vec.push_after_hole(value.clone());
+        }
+        hole_vec.move_hole_left(3);
+        drop(hole_vec.drain_before_hole(2).next());
+        std::mem::forget(hole_vec.drain_after_hole(2));
+        hole_vec
+            .splice(1..5, vec![value.clone(), value.clone()])
+            .next_back();
+        // The two values in the forgotten `Drain` are leaked
+        assert_eq!(std::rc::Rc::strong_count(&value), 1 + 2 + 14);
+
+        let copy = hole_vec.clone();
+        assert_eq!(std::rc::Rc::strong_count(&value), 1 + 2 + 28);
+        drop(hole_vec);
+        drop(copy.into_iter().next());
+        assert_eq!(std::rc::Rc::strong_count(&value), 1 + 2);
+    }
+}